@@ -7,6 +7,7 @@ cargo build --release
 # 実行（ファイル出力）
 ./target/release/triton ./sample/erDiagram.mermaid output_commands.sh
 
-# 生成されたコマンドファイルを実行可能にして実行
-chmod +x output_commands.sh
-./output_commands.sh
\ No newline at end of file
+# 実行可能なスクリプトとして出力し、Locoプロジェクトのルートで実行
+# （出力したファイルには実行権限が付与される）
+./target/release/triton --emit script ./sample/erDiagram.mermaid generate_models.sh
+./generate_models.sh
@@ -0,0 +1,186 @@
+use crate::diagnostic::Diagnostic;
+use crate::error::Span;
+use crate::types::*;
+use std::collections::{HashMap, HashSet};
+
+// 子エンティティが親エンティティを参照している（親を先に作る必要がある）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyEdge {
+    pub child: String,
+    pub parent: String,
+    pub source: EdgeSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeSource {
+    // scaffoldに含まれる `xxx_id FK` 属性
+    ForeignKey { attribute: String },
+    // リレーションシップから生成される参照
+    Relationship { span: Option<Span> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyOrder {
+    // scaffoldを実行する順のエンティティ名
+    pub order: Vec<String>,
+    // 循環を解消するため、後続のマイグレーションに回した参照
+    pub deferred: Vec<DependencyEdge>,
+    // 見つかった循環（エンティティ名の並び）
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl DependencyOrder {
+    pub fn is_deferred_foreign_key(&self, entity: &str, attribute: &str) -> bool {
+        self.deferred.iter().any(|edge| {
+            edge.child == entity
+                && matches!(&edge.source, EdgeSource::ForeignKey { attribute: a } if a == attribute)
+        })
+    }
+
+    // 循環ごとに、どの参照を後続のマイグレーションに回したかを説明する
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.cycles
+            .iter()
+            .zip(&self.deferred)
+            .map(|(cycle, edge)| {
+                let help = match &edge.source {
+                    EdgeSource::ForeignKey { attribute } => format!(
+                        "{}.{} の参照をscaffoldから外し、全テーブル作成後のマイグレーションで追加します",
+                        edge.child, attribute
+                    ),
+                    EdgeSource::Relationship { .. } => format!(
+                        "{} から {} への参照は、全テーブル作成後のマイグレーションで追加します",
+                        edge.child, edge.parent
+                    ),
+                };
+                let span = match &edge.source {
+                    EdgeSource::Relationship { span } => span.clone(),
+                    EdgeSource::ForeignKey { .. } => None,
+                };
+                Diagnostic::warning(format!("参照が循環しています: {}", cycle.join(" → ")))
+                    .with_span(span)
+                    .with_help(help)
+            })
+            .collect()
+    }
+}
+
+// FK属性とリレーションシップから依存関係を集める（自己参照は順序に影響しないので除く）
+// FK属性で既に参照しているリレーションシップは同じ参照なので、辺を重ねない
+pub fn dependency_edges(schema: &Schema) -> Vec<DependencyEdge> {
+    let mut edges = Vec::new();
+
+    for entity in &schema.entities {
+        for attr in entity.attributes.iter().filter(|a| a.is_foreign_key) {
+            if let Some(target) = schema.foreign_key_target(entity, attr)
+                && target.name != entity.name
+            {
+                edges.push(DependencyEdge {
+                    child: entity.name.clone(),
+                    parent: target.name.clone(),
+                    source: EdgeSource::ForeignKey { attribute: attr.name.clone() },
+                });
+            }
+        }
+    }
+
+    for relationship in &schema.relationships {
+        if let Some((parent, child)) = relationship.parent_and_child() {
+            let both_defined = schema.find_entity(parent).is_some() && schema.find_entity(child).is_some();
+            let has_foreign_key = edges.iter().any(|edge| {
+                edge.child == child && edge.parent == parent && matches!(edge.source, EdgeSource::ForeignKey { .. })
+            });
+            if parent != child && both_defined && !has_foreign_key {
+                edges.push(DependencyEdge {
+                    child: child.to_string(),
+                    parent: parent.to_string(),
+                    source: EdgeSource::Relationship { span: relationship.span.clone() },
+                });
+            }
+        }
+    }
+
+    edges
+}
+
+// 親が子より先になるようにエンティティを並べる
+// 依存関係の無いエンティティ同士は図に書かれた順を保つ
+pub fn topological_order(schema: &Schema) -> DependencyOrder {
+    let mut edges = dependency_edges(schema);
+    let mut order = Vec::new();
+    let mut deferred = Vec::new();
+    let mut cycles = Vec::new();
+    let mut placed: HashSet<&str> = HashSet::new();
+    // 同じ名前のエンティティは最初の定義だけを並べる
+    let mut names: HashSet<&str> = HashSet::new();
+    let entities: Vec<&Entity> = schema.entities.iter().filter(|e| names.insert(e.name.as_str())).collect();
+
+    while placed.len() < entities.len() {
+        let remaining: Vec<&Entity> = entities
+            .iter()
+            .copied()
+            .filter(|e| !placed.contains(e.name.as_str()))
+            .collect();
+
+        // 未配置の親を持たない最初のエンティティ
+        let ready = remaining.iter().find(|e| {
+            !edges
+                .iter()
+                .any(|edge| edge.child == e.name && !placed.contains(edge.parent.as_str()))
+        });
+
+        match ready {
+            Some(entity) => {
+                order.push(entity.name.clone());
+                placed.insert(entity.name.as_str());
+            }
+            None => {
+                // 全て循環の中にある: 循環を1つ見つけ、1本の参照を後回しにする
+                let (cycle, edge_index) = find_cycle(&remaining, &edges, &placed);
+                cycles.push(cycle);
+                deferred.push(edges.remove(edge_index));
+            }
+        }
+    }
+
+    DependencyOrder { order, deferred, cycles }
+}
+
+// 図で最初に出てくる未配置エンティティから親をたどって循環を見つける
+// 後回しにする参照は、既にマイグレーションになるリレーションシップ由来のものを優先し、
+// 無ければ循環の起点となるエンティティの参照を選ぶ
+fn find_cycle(remaining: &[&Entity], edges: &[DependencyEdge], placed: &HashSet<&str>) -> (Vec<String>, usize) {
+    let unmet = |name: &str| -> Vec<usize> {
+        edges
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.child == name && !placed.contains(e.parent.as_str()))
+            .map(|(i, _)| i)
+            .collect()
+    };
+
+    let mut path: Vec<String> = Vec::new();
+    let mut path_edges: Vec<usize> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut current = remaining[0].name.clone();
+
+    loop {
+        if let Some(&start) = seen.get(&current) {
+            let mut cycle: Vec<String> = path[start..].to_vec();
+            cycle.push(current);
+            let cycle_edges = &path_edges[start..];
+            let chosen = cycle_edges
+                .iter()
+                .copied()
+                .find(|&i| matches!(edges[i].source, EdgeSource::Relationship { .. }))
+                .unwrap_or(cycle_edges[0]);
+            return (cycle, chosen);
+        }
+        seen.insert(current.clone(), path.len());
+        path.push(current.clone());
+
+        let edge_index = unmet(&current)[0];
+        path_edges.push(edge_index);
+        current = edges[edge_index].parent.clone();
+    }
+}
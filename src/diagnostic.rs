@@ -0,0 +1,183 @@
+use crate::error::{ParseError, Span};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Option<Span>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span: None,
+            help: None,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span: None,
+            help: None,
+        }
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_span(mut self, span: Option<Span>) -> Self {
+        self.span = span;
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    // パースエラーを指定した重大度の診断に変換する
+    pub fn from_parse_error(error: &ParseError, severity: Severity) -> Self {
+        let help = match error {
+            ParseError::UnrecognizedLine { .. } => {
+                "エンティティは `名前 {`、リレーションシップは `A ||--o{ B : \"ラベル\"` の形式で記述してください"
+            }
+            ParseError::InvalidAttribute { .. } => {
+                "属性は `型名 カラム名 [PK,FK] \"コメント\"` の形式で記述してください"
+            }
+            ParseError::UnclosedEntity { .. } => "ブロックの最後に `}` を追加してください",
+            ParseError::DanglingAnnotation { .. } => {
+                "注釈はエンティティの `名前 {` の行か、属性の行の直前に記述してください"
+            }
+            ParseError::InvalidTypeParameters { .. } => {
+                "`decimal_len(10,2)` / `binary_len(16)` / `array<string>` の形式で指定してください（lenientモードでは引数の要らない decimal / blob / array:string として扱います）"
+            }
+            ParseError::UnsupportedConstraint { .. } => {
+                "`^` / `UK` を外すか、ユニーク制約を指定できる型を使ってください（lenientモードでは必須（`!`）として扱います）"
+            }
+            ParseError::UnknownType { .. } => {
+                "Locoの型名（string, int, bool, tstz など）か、varchar・integer・boolean・timestamp などの別名を指定してください（lenientモードでは string として扱います）"
+            }
+        };
+        Self {
+            severity,
+            message: error.message(),
+            span: Some(error.span().clone()),
+            help: Some(help.to_string()),
+        }
+    }
+}
+
+// 1回の実行で見つかった診断をまとめて表示する
+pub struct DiagnosticReporter {
+    file_name: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticReporter {
+    pub fn new(file_name: impl Into<String>) -> Self {
+        Self {
+            file_name: file_name.into(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn extend(&mut self, diagnostics: impl IntoIterator<Item = Diagnostic>) {
+        self.diagnostics.extend(diagnostics);
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn render(&self) -> String {
+        // ソース上の位置順に並べ、位置の無いものは最後にまとめる
+        let mut sorted: Vec<&Diagnostic> = self.diagnostics.iter().collect();
+        sorted.sort_by_key(|d| d.span.as_ref().map(|s| (s.line, s.column)).unwrap_or((usize::MAX, 0)));
+
+        let mut output = String::new();
+        for diagnostic in sorted {
+            output.push_str(&self.render_one(diagnostic));
+            output.push('\n');
+        }
+
+        let errors = self.diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+        let warnings = self.diagnostics.len() - errors;
+        if !self.diagnostics.is_empty() {
+            output.push_str(&format!("{}件のエラー、{}件の警告\n", errors, warnings));
+        }
+        output
+    }
+
+    // error: メッセージ
+    //   --> ファイル名:行:列
+    //    |
+    //  4 |     該当行
+    //    |     ^^^^^^
+    //    = help: ヒント
+    fn render_one(&self, diagnostic: &Diagnostic) -> String {
+        let mut lines = vec![format!("{}: {}", diagnostic.severity, diagnostic.message)];
+
+        let gutter_width = diagnostic
+            .span
+            .as_ref()
+            .map(|span| span.line.to_string().len())
+            .unwrap_or(1);
+        let gutter = " ".repeat(gutter_width);
+
+        match &diagnostic.span {
+            Some(span) => {
+                lines.push(format!("{}--> {}:{}:{}", gutter, self.file_name, span.line, span.column));
+                lines.push(format!("{} |", gutter));
+                lines.push(format!("{} | {}", span.line, span.snippet.trim_end()));
+                // タブ幅がずれないよう、該当行の文字を空白に置き換えて位置を合わせる
+                let indent: String = span
+                    .snippet
+                    .chars()
+                    .take(span.column - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                lines.push(format!("{} | {}{}", gutter, indent, "^".repeat(span.length)));
+            }
+            None => {
+                lines.push(format!("{}--> {}", gutter, self.file_name));
+            }
+        }
+
+        if let Some(help) = &diagnostic.help {
+            lines.push(format!("{} = help: {}", gutter, help));
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
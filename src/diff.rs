@@ -0,0 +1,175 @@
+use crate::diagnostic::Diagnostic;
+use crate::naming::to_pascal_case;
+use crate::types::*;
+
+// 2つの図の差分（エンティティはモデル名、属性はカラム名で対応させる）
+#[derive(Debug, Clone)]
+pub struct SchemaDiff<'a> {
+    pub old: &'a Schema,
+    pub new: &'a Schema,
+    // 新しい図にだけあるエンティティ
+    pub added_entities: Vec<&'a Entity>,
+    // 古い図にだけあるエンティティ（古い図の要素）
+    pub removed_entities: Vec<&'a Entity>,
+    // 両方にあるエンティティに追加された属性
+    pub added_columns: Vec<(&'a Entity, Vec<&'a Attribute>)>,
+    // 両方にあるエンティティから削除された属性（古い図の要素）
+    pub removed_columns: Vec<(&'a Entity, Vec<&'a Attribute>)>,
+    // 型・制約が変わった属性: (新しい図のエンティティ, 古い属性, 新しい属性)
+    pub changed_columns: Vec<(&'a Entity, &'a Attribute, &'a Attribute)>,
+    // 新しい図にだけあるリレーションシップ
+    pub added_relationships: Vec<&'a Relationship>,
+    // 古い図にだけあるリレーションシップ（古い図の要素）
+    pub removed_relationships: Vec<&'a Relationship>,
+}
+
+impl<'a> SchemaDiff<'a> {
+    pub fn between(old: &'a Schema, new: &'a Schema) -> Self {
+        let find = |schema: &'a Schema, entity: &Entity| {
+            schema.entities.iter().find(|e| e.model_name() == entity.model_name())
+        };
+
+        let mut diff = Self {
+            old,
+            new,
+            added_entities: new.entities.iter().filter(|e| find(old, e).is_none()).collect(),
+            removed_entities: old.entities.iter().filter(|e| find(new, e).is_none()).collect(),
+            added_columns: Vec::new(),
+            removed_columns: Vec::new(),
+            changed_columns: Vec::new(),
+            added_relationships: Vec::new(),
+            removed_relationships: Vec::new(),
+        };
+
+        for entity in &new.entities {
+            let Some(previous) = find(old, entity) else {
+                continue;
+            };
+            let attribute = |e: &'a Entity, name: &str| e.attributes.iter().find(|a| a.name == name);
+
+            let added: Vec<&Attribute> = entity.attributes.iter().filter(|a| attribute(previous, &a.name).is_none()).collect();
+            if !added.is_empty() {
+                diff.added_columns.push((entity, added));
+            }
+            let removed: Vec<&Attribute> = previous.attributes.iter().filter(|a| attribute(entity, &a.name).is_none()).collect();
+            if !removed.is_empty() {
+                diff.removed_columns.push((previous, removed));
+            }
+            for attr in &entity.attributes {
+                if let Some(before) = attribute(previous, &attr.name)
+                    && (LocoDataType::from_mermaid_type(&before.data_type) != LocoDataType::from_mermaid_type(&attr.data_type)
+                        || before.is_foreign_key != attr.is_foreign_key)
+                {
+                    diff.changed_columns.push((entity, before, attr));
+                }
+            }
+        }
+
+        let keys = |schema: &Schema| -> Vec<RelationshipKey> {
+            schema.relationships.iter().map(|r| RelationshipKey::new(schema, r)).collect()
+        };
+        let (old_keys, new_keys) = (keys(old), keys(new));
+        diff.added_relationships = new
+            .relationships
+            .iter()
+            .zip(&new_keys)
+            .filter(|(_, key)| !old_keys.contains(key))
+            .map(|(r, _)| r)
+            .collect();
+        diff.removed_relationships = old
+            .relationships
+            .iter()
+            .zip(&old_keys)
+            .filter(|(_, key)| !new_keys.contains(key))
+            .map(|(r, _)| r)
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_entities.is_empty()
+            && self.removed_entities.is_empty()
+            && self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.changed_columns.is_empty()
+            && self.added_relationships.is_empty()
+            && self.removed_relationships.is_empty()
+    }
+
+    // コマンドにできない変更（型の変更・リレーションシップの削除）
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (entity, before, after) in &self.changed_columns {
+            diagnostics.push(
+                Diagnostic::warning(format!(
+                    "{}.{} の型が {} から {} に変わっていますが、カラムの変更は生成しません",
+                    entity.name,
+                    after.name,
+                    describe(before),
+                    describe(after)
+                ))
+                .with_help("データを保ったまま変更するマイグレーションを手動で追加してください"),
+            );
+        }
+        // Locoは空のマイグレーションを作るだけなので、テーブルの削除は手動で記述してもらう
+        for entity in &self.removed_entities {
+            let table = entity.table_name();
+            diagnostics.push(
+                Diagnostic::warning(format!(
+                    "テーブル '{}' を削除する Drop{} のマイグレーションは空のまま生成されます",
+                    table,
+                    to_pascal_case(&table)
+                ))
+                .with_help(format!("生成されたマイグレーションの `up` に `drop_table(m, \"{}\")` を記述してください", table)),
+            );
+        }
+        // 削除されたエンティティのリレーションシップはテーブルごと削除される
+        let removed = |name: &str| self.removed_entities.iter().any(|e| e.name == name);
+        for relationship in &self.removed_relationships {
+            if removed(&relationship.from_entity) || removed(&relationship.to_entity) {
+                continue;
+            }
+            diagnostics.push(
+                Diagnostic::warning(format!(
+                    "{} と {} のリレーションシップの削除は生成しません",
+                    relationship.from_entity, relationship.to_entity
+                ))
+                .with_help("参照カラム・中間テーブルを削除するマイグレーションを手動で追加してください"),
+            );
+        }
+        diagnostics
+    }
+}
+
+// `int` / `int FK` のような型の表記
+fn describe(attr: &Attribute) -> String {
+    let data_type = LocoDataType::from_mermaid_type(&attr.data_type);
+    if attr.is_foreign_key { format!("{} FK", data_type) } else { data_type.to_string() }
+}
+
+// 記述の向き・ラベルに関係なく同じ参照を表すリレーションシップを同一とみなす
+#[derive(Debug, PartialEq)]
+struct RelationshipKey {
+    relationship_type: RelationshipType,
+    // 1対多・1対1は (親, 子)、多対多は名前順のモデル名
+    ends: (String, String),
+}
+
+impl RelationshipKey {
+    fn new(schema: &Schema, relationship: &Relationship) -> Self {
+        let (first, second) = relationship
+            .parent_and_child()
+            .unwrap_or((&relationship.from_entity, &relationship.to_entity));
+        let (first, second) = (schema.model_name_of(first), schema.model_name_of(second));
+        let ends = if relationship.relationship_type == RelationshipType::ManyToMany && first > second {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        Self {
+            relationship_type: relationship.relationship_type.clone(),
+            ends,
+        }
+    }
+}
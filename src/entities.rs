@@ -0,0 +1,427 @@
+use crate::generator::ManagedColumn;
+use crate::migration::{foreign_keys, join_tables, ForeignKey};
+use crate::naming::{to_pascal_case, to_snake_case};
+use crate::types::*;
+use std::collections::{BTreeMap, HashSet};
+
+// `src/models/_entities` に置くSeaORMのエンティティモジュール
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityFile {
+    // `posts` のようなモジュール名（テーブル名）
+    pub module: String,
+    pub content: String,
+}
+
+impl EntityFile {
+    // Locoプロジェクトのルートからの相対パス
+    pub fn path(&self) -> String {
+        format!("src/models/_entities/{}.rs", self.module)
+    }
+}
+
+const HEADER: &str = "//! `SeaORM` Entity, @generated by triton";
+
+// マイグレーションと同じSchemaから、sea-orm-cli と同じ形のエンティティを作る
+// `create_table` が作るテーブル（created_at / updated_at / id、参照カラム）に合わせる
+pub struct EntityGenerator {
+    // マイグレーションが列に含めないカラム（created_at / updated_at は常に先頭に置く）
+    managed_columns: Vec<ManagedColumn>,
+}
+
+impl Default for EntityGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Relation enum の1要素
+struct RelationVariant {
+    name: String,
+    // `#[sea_orm(...)]` の中身
+    attribute: String,
+    // `Related<T>` を実装する相手のモジュール（同じ相手への関係が複数あれば実装しない）
+    related: Option<String>,
+}
+
+// モデルのテーブルと、そのエンティティモジュールの列・参照
+struct Table<'a> {
+    module: String,
+    entity: Option<&'a Entity>,
+    foreign_keys: Vec<ForeignKey<'a>>,
+    // 中間テーブルの場合の両端のエンティティ
+    join: Option<(&'a Entity, &'a Entity)>,
+}
+
+impl EntityGenerator {
+    pub fn new() -> Self {
+        Self {
+            managed_columns: ManagedColumn::loco_defaults(),
+        }
+    }
+
+    pub fn with_managed_columns(mut self, managed_columns: Vec<ManagedColumn>) -> Self {
+        self.managed_columns = managed_columns;
+        self
+    }
+
+    // エンティティごとのモジュールと `mod.rs` / `prelude.rs`
+    pub fn generate(&self, schema: &Schema) -> Vec<EntityFile> {
+        let tables = self.tables(schema);
+        let mut files: Vec<EntityFile> = tables
+            .iter()
+            .map(|table| EntityFile {
+                module: table.module.clone(),
+                content: self.render_entity(table, &tables),
+            })
+            .collect();
+        files.sort_by(|a, b| a.module.cmp(&b.module));
+
+        let modules: Vec<&str> = files.iter().map(|f| f.module.as_str()).collect();
+        let mut mod_rs = vec![HEADER.to_string(), String::new(), "pub mod prelude;".to_string(), String::new()];
+        mod_rs.extend(modules.iter().map(|m| format!("pub mod {};", m)));
+        let mut prelude_rs = vec![HEADER.to_string(), String::new()];
+        prelude_rs.extend(
+            modules
+                .iter()
+                .map(|m| format!("pub use super::{}::Entity as {};", m, to_pascal_case(m))),
+        );
+
+        files.push(EntityFile {
+            module: "mod".to_string(),
+            content: mod_rs.join("\n") + "\n",
+        });
+        files.push(EntityFile {
+            module: "prelude".to_string(),
+            content: prelude_rs.join("\n") + "\n",
+        });
+        files
+    }
+
+    fn tables<'a>(&self, schema: &'a Schema) -> Vec<Table<'a>> {
+        let mut tables: Vec<Table<'a>> = schema
+            .entities
+            .iter()
+            .map(|entity| Table {
+                module: entity.table_name(),
+                entity: Some(entity),
+                foreign_keys: foreign_keys(schema, entity),
+                join: None,
+            })
+            .collect();
+
+        for join_table in join_tables(schema) {
+            let (Some(from), Some(to)) = (schema.find_entity(&join_table.from), schema.find_entity(&join_table.to)) else {
+                continue;
+            };
+            if from.name == to.name {
+                continue;
+            }
+            tables.push(Table {
+                module: join_table.table,
+                entity: None,
+                foreign_keys: Vec::new(),
+                join: Some((from, to)),
+            });
+        }
+        tables
+    }
+
+    fn render_entity(&self, table: &Table, tables: &[Table]) -> String {
+        let mut fields = vec![
+            "    pub created_at: DateTimeWithTimeZone,".to_string(),
+            "    pub updated_at: DateTimeWithTimeZone,".to_string(),
+        ];
+        let mut has_float = false;
+
+        match (table.entity, table.join) {
+            (Some(entity), _) => {
+                if entity.has_uuid_primary_key() {
+                    fields.push("    #[sea_orm(primary_key, auto_increment = false)]".to_string());
+                    fields.push("    pub id: Uuid,".to_string());
+                } else {
+                    fields.push("    #[sea_orm(primary_key)]".to_string());
+                    fields.push("    pub id: i32,".to_string());
+                }
+
+                for attr in &entity.attributes {
+                    if attr.is_primary_key || attr.is_foreign_key || self.managed_columns.iter().any(|c| c.name == attr.name) {
+                        continue;
+                    }
+                    let data_type = LocoDataType::from_mermaid_type(&attr.data_type);
+                    let (rust_type, column_type) = rust_type(&data_type);
+                    has_float |= rust_type.contains("f32") || rust_type.contains("f64");
+
+                    let mut options = Vec::new();
+                    let nullable = data_type.constraint == Constraint::Nullable;
+                    if let Some(column_type) = column_type {
+                        options.push(format!("column_type = \"{}\"", column_type));
+                        if nullable {
+                            options.push("nullable".to_string());
+                        }
+                    }
+                    if data_type.constraint == Constraint::Unique {
+                        options.push("unique".to_string());
+                    }
+                    if RAW_INCOMPATIBLE_KEYWORDS.contains(&attr.name.as_str()) {
+                        options.push(format!("column_name = \"{}\"", attr.name));
+                    }
+                    if !options.is_empty() {
+                        fields.push(format!("    #[sea_orm({})]", options.join(", ")));
+                    }
+                    let rust_type = if nullable { format!("Option<{}>", rust_type) } else { rust_type };
+                    fields.push(format!("    pub {}: {},", field_name(&attr.name), rust_type));
+                }
+
+                for foreign_key in &table.foreign_keys {
+                    fields.push(format!("    pub {}: {},", foreign_key.column, reference_type(foreign_key.target)));
+                }
+            }
+            (None, Some((from, to))) => {
+                for entity in [from, to] {
+                    fields.push("    #[sea_orm(primary_key, auto_increment = false)]".to_string());
+                    fields.push(format!("    pub {}: {},", join_column(entity), reference_type(Some(entity))));
+                }
+            }
+            (None, None) => unreachable!("tables are either entities or join tables"),
+        }
+
+        let variants = self.relation_variants(table, tables);
+        let eq = if has_float { "" } else { ", Eq" };
+
+        let mut lines = vec![
+            HEADER.to_string(),
+            String::new(),
+            "use sea_orm::entity::prelude::*;".to_string(),
+            "use serde::{Deserialize, Serialize};".to_string(),
+            String::new(),
+            format!("#[derive(Clone, Debug, PartialEq, DeriveEntityModel{}, Serialize, Deserialize)]", eq),
+            format!("#[sea_orm(table_name = \"{}\")]", table.module),
+            "pub struct Model {".to_string(),
+        ];
+        lines.extend(fields);
+        lines.push("}".to_string());
+        lines.push(String::new());
+        lines.push("#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]".to_string());
+        if variants.is_empty() {
+            lines.push("pub enum Relation {}".to_string());
+        } else {
+            lines.push("pub enum Relation {".to_string());
+            for variant in &variants {
+                lines.push(format!("    #[sea_orm({})]", variant.attribute));
+                lines.push(format!("    {},", variant.name));
+            }
+            lines.push("}".to_string());
+        }
+
+        // `Related<T>`: 直接の関係 → 中間テーブル経由（`via`）の順
+        let mut related: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for variant in &variants {
+            if let Some(module) = &variant.related {
+                related.insert(
+                    module.clone(),
+                    vec![
+                        "    fn to() -> RelationDef {".to_string(),
+                        format!("        Relation::{}.def()", variant.name),
+                        "    }".to_string(),
+                    ],
+                );
+            }
+        }
+        if let Some(entity) = table.entity {
+            for join in tables.iter().filter(|t| t.join.is_some()) {
+                let (from, to) = join.join.expect("filtered to join tables");
+                let other = match (from.name == entity.name, to.name == entity.name) {
+                    (true, _) => to,
+                    (_, true) => from,
+                    _ => continue,
+                };
+                related.entry(other.table_name()).or_insert_with(|| {
+                    vec![
+                        "    fn to() -> RelationDef {".to_string(),
+                        format!("        super::{}::Relation::{}.def()", join.module, to_pascal_case(&other.table_name())),
+                        "    }".to_string(),
+                        String::new(),
+                        "    fn via() -> Option<RelationDef> {".to_string(),
+                        format!(
+                            "        Some(super::{}::Relation::{}.def().rev())",
+                            join.module,
+                            to_pascal_case(&entity.table_name())
+                        ),
+                        "    }".to_string(),
+                    ]
+                });
+            }
+        }
+        for (module, body) in related {
+            lines.push(String::new());
+            lines.push(format!("impl Related<super::{}::Entity> for Entity {{", module));
+            lines.extend(body);
+            lines.push("}".to_string());
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    // belongs_to（このテーブルの参照）→ has_one / has_many（このテーブルへの参照）の順
+    fn relation_variants(&self, table: &Table, tables: &[Table]) -> Vec<RelationVariant> {
+        let mut variants: Vec<RelationVariant> = Vec::new();
+
+        // このテーブルが持つ参照
+        let belongs_to: Vec<(String, &Entity)> = match (table.entity, table.join) {
+            (Some(_), _) => table
+                .foreign_keys
+                .iter()
+                .filter_map(|fk| fk.target.map(|target| (fk.column.clone(), target)))
+                .collect(),
+            (None, Some((from, to))) => vec![(join_column(from), from), (join_column(to), to)],
+            (None, None) => Vec::new(),
+        };
+        for (column, target) in &belongs_to {
+            let is_self = table.entity.is_some_and(|e| e.name == target.name);
+            let duplicated = belongs_to.iter().filter(|(_, t)| t.name == target.name).count() > 1;
+            let (entity_path, column_path) = if is_self {
+                ("Entity".to_string(), "Column::Id".to_string())
+            } else {
+                let module = target.table_name();
+                (format!("super::{}::Entity", module), format!("super::{}::Column::Id", module))
+            };
+            let name = match (is_self, duplicated) {
+                (true, true) => format!("SelfRef{}", to_pascal_case(column)),
+                (true, false) => "SelfRef".to_string(),
+                (false, true) => format!("{}{}", to_pascal_case(&target.table_name()), to_pascal_case(column)),
+                (false, false) => to_pascal_case(&target.table_name()),
+            };
+            variants.push(RelationVariant {
+                name,
+                attribute: format!(
+                    "\n        belongs_to = \"{}\",\n        from = \"Column::{}\",\n        to = \"{}\",\n        on_update = \"Cascade\",\n        on_delete = \"Cascade\"\n    ",
+                    entity_path,
+                    to_pascal_case(column),
+                    column_path
+                ),
+                related: (!is_self && !duplicated).then(|| target.table_name()),
+            });
+        }
+
+        // 他のテーブルからの参照（同じテーブルから複数ある場合は向きが決まらないので出力しない）
+        let Some(entity) = table.entity else {
+            return variants;
+        };
+        for other in tables {
+            if other.module == table.module {
+                continue;
+            }
+            let (kind, count) = match other.join {
+                Some((from, to)) => ("has_many", usize::from(from.name == entity.name) + usize::from(to.name == entity.name)),
+                None => {
+                    let incoming: Vec<&ForeignKey> = other
+                        .foreign_keys
+                        .iter()
+                        .filter(|fk| fk.target.is_some_and(|t| t.name == entity.name))
+                        .collect();
+                    let kind = if incoming.first().is_some_and(|fk| fk.is_one_to_one) { "has_one" } else { "has_many" };
+                    (kind, incoming.len())
+                }
+            };
+            if count != 1 {
+                continue;
+            }
+            variants.push(RelationVariant {
+                name: to_pascal_case(&other.module),
+                attribute: format!("{} = \"super::{}::Entity\"", kind, other.module),
+                related: Some(other.module.clone()),
+            });
+        }
+
+        // 同じ相手への `Related` は1つだけにする
+        let mut seen = HashSet::new();
+        for variant in &mut variants {
+            if let Some(module) = &variant.related
+                && !seen.insert(module.clone())
+            {
+                variant.related = None;
+            }
+        }
+        variants
+    }
+}
+
+// 中間テーブルの参照カラム（`post_id`）
+fn join_column(entity: &Entity) -> String {
+    format!("{}_id", to_snake_case(&entity.model_name()))
+}
+
+// Rustのキーワード（sea-orm-cliと同じく生識別子にする）
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false", "fn", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct", "trait",
+    "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+// 生識別子にできないキーワード（末尾に `_` を付け、`column_name` で元のカラム名を指定する）
+const RAW_INCOMPATIBLE_KEYWORDS: &[&str] = &["crate", "self", "super", "Self"];
+
+// カラム名をフィールド名として使える形にする
+fn field_name(column: &str) -> String {
+    if RAW_INCOMPATIBLE_KEYWORDS.contains(&column) {
+        format!("{}_", column)
+    } else if RUST_KEYWORDS.contains(&column) {
+        format!("r#{}", column)
+    } else {
+        column.to_string()
+    }
+}
+
+// 参照先の主キーと同じ型
+fn reference_type(target: Option<&Entity>) -> &'static str {
+    if target.is_some_and(Entity::has_uuid_primary_key) { "Uuid" } else { "i32" }
+}
+
+// Locoの型に対応するRustの型と、既定の対応と異なる場合の `column_type`
+pub fn rust_type(data_type: &LocoDataType) -> (String, Option<String>) {
+    let data_type = data_type.fill_missing_params();
+    let (rust_type, column_type) = match data_type.base {
+        LocoBaseType::Uuid => ("Uuid", None),
+        LocoBaseType::String => ("String", None),
+        LocoBaseType::Text => ("String", Some("Text".to_string())),
+        LocoBaseType::SmallUnsigned => ("u16", None),
+        LocoBaseType::BigUnsigned => ("u64", None),
+        LocoBaseType::Unsigned => ("u32", None),
+        LocoBaseType::SmallInt => ("i16", None),
+        LocoBaseType::Int => ("i32", None),
+        LocoBaseType::BigInt => ("i64", None),
+        LocoBaseType::Float => ("f32", None),
+        LocoBaseType::Double => ("f64", None),
+        LocoBaseType::Decimal => ("Decimal", None),
+        LocoBaseType::DecimalLen => match data_type.params {
+            Some(TypeParams::Precision { precision, scale }) => {
+                ("Decimal", Some(format!("Decimal(Some(({}, {})))", precision, scale)))
+            }
+            _ => ("Decimal", None),
+        },
+        LocoBaseType::Money => ("Decimal", Some("Money(None)".to_string())),
+        LocoBaseType::Bool => ("bool", None),
+        LocoBaseType::Tstz => ("DateTimeWithTimeZone", None),
+        LocoBaseType::Date => ("Date", None),
+        LocoBaseType::DateTime => ("DateTime", None),
+        LocoBaseType::Blob => ("Vec<u8>", Some("Blob".to_string())),
+        LocoBaseType::BinaryLen => match data_type.params {
+            Some(TypeParams::Length(length)) => ("Vec<u8>", Some(format!("Binary({})", length))),
+            _ => ("Vec<u8>", Some("Blob".to_string())),
+        },
+        LocoBaseType::VarBinary => ("Vec<u8>", Some("VarBinary(StringLen::None)".to_string())),
+        LocoBaseType::Json => ("Json", Some("Json".to_string())),
+        LocoBaseType::JsonBinary => ("Json", Some("JsonBinary".to_string())),
+        LocoBaseType::Array => {
+            let element = match data_type.params {
+                Some(TypeParams::Element(LocoBaseType::Int)) => "i32",
+                Some(TypeParams::Element(LocoBaseType::Float)) => "f32",
+                Some(TypeParams::Element(LocoBaseType::Bool)) => "bool",
+                _ => "String",
+            };
+            return (format!("Vec<{}>", element), None);
+        }
+    };
+    (rust_type.to_string(), column_type)
+}
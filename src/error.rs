@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// エラー箇所（行・列は1始まり）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    // 下線を引く文字数
+    pub length: usize,
+    // 該当行のソース全体
+    pub snippet: String,
+}
+
+impl Span {
+    // 行頭の空白を除いた、行の中身全体を指すSpan
+    pub fn from_line(line: usize, raw_line: &str) -> Self {
+        let trimmed = raw_line.trim();
+        let offset = raw_line.len() - raw_line.trim_start().len();
+        Self {
+            line,
+            column: raw_line[..offset].chars().count() + 1,
+            length: trimmed.chars().count().max(1),
+            snippet: raw_line.to_string(),
+        }
+    }
+
+    // 行内の特定の文字列を指すSpan（見つからなければ行全体）
+    pub fn find(line: usize, raw_line: &str, needle: &str) -> Self {
+        match raw_line.find(needle) {
+            Some(offset) => Self {
+                line,
+                column: raw_line[..offset].chars().count() + 1,
+                length: needle.chars().count().max(1),
+                snippet: raw_line.to_string(),
+            },
+            None => Self::from_line(line, raw_line),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    // エンティティ・リレーションシップのどちらとも解釈できない行
+    UnrecognizedLine { span: Span },
+    // エンティティのブロック内で `型名 カラム名 [PK,FK] コメント` に沿わない行
+    InvalidAttribute { entity: String, span: Span },
+    // `{` に対応する `}` が無いまま次のエンティティ・ファイル末尾に達した
+    UnclosedEntity { entity: String, span: Span },
+    // `%% triton:` 注釈の直後にエンティティ・属性が無い
+    DanglingAnnotation { span: Span },
+    // Locoの型にも既知の別名にも当てはまらない型名
+    UnknownType { entity: String, attribute: String, type_name: String, span: Span },
+    // `decimal_len(10,2)` などの型の引数が不正
+    InvalidTypeParameters { entity: String, attribute: String, error: DataTypeError, span: Span },
+    // `bool^` のように、Locoではユニーク制約を指定できない型
+    UnsupportedConstraint { entity: String, attribute: String, type_name: String, span: Span },
+}
+
+impl ParseError {
+    pub fn span(&self) -> &Span {
+        match self {
+            ParseError::UnrecognizedLine { span }
+            | ParseError::InvalidAttribute { span, .. }
+            | ParseError::UnclosedEntity { span, .. }
+            | ParseError::DanglingAnnotation { span }
+            | ParseError::UnknownType { span, .. }
+            | ParseError::InvalidTypeParameters { span, .. }
+            | ParseError::UnsupportedConstraint { span, .. } => span,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::UnrecognizedLine { .. } => "解釈できない行です".to_string(),
+            ParseError::InvalidAttribute { entity, .. } => {
+                format!("エンティティ '{}' の属性として解釈できません", entity)
+            }
+            ParseError::UnclosedEntity { entity, .. } => {
+                format!("エンティティ '{}' の `{{` が閉じられていません", entity)
+            }
+            ParseError::DanglingAnnotation { .. } => {
+                "`%% triton:` 注釈の対象となるエンティティ・属性がありません".to_string()
+            }
+            ParseError::UnknownType { entity, attribute, type_name, .. } => {
+                format!("{}.{} の型 '{}' はLocoの型に変換できません", entity, attribute, type_name)
+            }
+            ParseError::InvalidTypeParameters { entity, attribute, error, .. } => {
+                format!("{}.{}: {}", entity, attribute, error)
+            }
+            ParseError::UnsupportedConstraint { entity, attribute, type_name, .. } => {
+                format!("{}.{} の型 '{}' にはユニーク制約を指定できません", entity, attribute, type_name)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.span();
+        write!(
+            f,
+            "{}行{}列目: {}: {}",
+            span.line,
+            span.column,
+            self.message(),
+            span.snippet.trim()
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// strictモードで見つかった全てのエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl ParseErrors {
+    pub fn errors(&self) -> &[ParseError] {
+        &self.0
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self.0.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", messages.join("\n"))
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+// Locoの型指定として解釈できない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataTypeError {
+    // 型名が不明
+    UnknownType(String),
+    // 型は既知だが、その修飾子に対応するLocoの型が無い（`bool^` など）
+    UnsupportedConstraint(String),
+    // 引数の数・範囲が不正（`decimal_len(0,2)` など）
+    InvalidParameters { type_name: String, reason: String },
+}
+
+impl fmt::Display for DataTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataTypeError::UnknownType(name) => write!(f, "'{}' はLocoの型ではありません", name),
+            DataTypeError::UnsupportedConstraint(name) => {
+                write!(f, "'{}' はLocoでは指定できません", name)
+            }
+            DataTypeError::InvalidParameters { type_name, reason } => {
+                write!(f, "'{}' の引数が不正です: {}", type_name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataTypeError {}
@@ -1,99 +1,399 @@
+use crate::dependency::{topological_order, EdgeSource};
+use crate::diagnostic::Diagnostic;
+use crate::diff::SchemaDiff;
+use crate::error::Span;
+use crate::naming::{to_pascal_case, to_snake_case, to_table_name};
 use crate::types::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
 
 pub struct LocoGenerator {
-    // 生成済みのテーブルを追跡
-    generated_tables: HashSet<String>,
+    // Locoが自動で作成するためscaffoldに含めないカラム
+    managed_columns: Vec<ManagedColumn>,
+    // 注釈・個別指定の無いエンティティに使う生成方法
+    scaffold_kind: ScaffoldKind,
+    // エンティティ名ごとの生成方法（注釈より優先する）
+    scaffold_overrides: BTreeMap<String, ScaffoldKind>,
+}
+
+// エンティティごとの生成方法
+// `%% triton: scaffold=model` の注釈か、CLIの `--scaffold Tag=model` で選ぶ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaffoldKind {
+    // `generate scaffold --api`
+    #[default]
+    Api,
+    // `generate scaffold --html`
+    Html,
+    // `generate scaffold --htmx`
+    Htmx,
+    // `generate model`（コントローラ・ビューを作らない）
+    Model,
+    // `generate controller --api`（モデル・テーブルを作らない）
+    Controller,
+}
+
+impl ScaffoldKind {
+    fn command(&self) -> (&'static str, Option<&'static str>) {
+        match self {
+            ScaffoldKind::Api => ("scaffold", Some("--api")),
+            ScaffoldKind::Html => ("scaffold", Some("--html")),
+            ScaffoldKind::Htmx => ("scaffold", Some("--htmx")),
+            ScaffoldKind::Model => ("model", None),
+            ScaffoldKind::Controller => ("controller", Some("--api")),
+        }
+    }
+
+    // モデルを作るか（コントローラだけの場合はカラムを渡さない）
+    pub fn creates_model(&self) -> bool {
+        *self != ScaffoldKind::Controller
+    }
+}
+
+impl FromStr for ScaffoldKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "api" => Ok(ScaffoldKind::Api),
+            "html" => Ok(ScaffoldKind::Html),
+            "htmx" => Ok(ScaffoldKind::Htmx),
+            "model" => Ok(ScaffoldKind::Model),
+            "controller" => Ok(ScaffoldKind::Controller),
+            _ => Err(format!("'{}' は生成方法ではありません（api・html・htmx・model・controller のいずれか）", s)),
+        }
+    }
+}
+
+impl fmt::Display for ScaffoldKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldKind::Api => write!(f, "api"),
+            ScaffoldKind::Html => write!(f, "html"),
+            ScaffoldKind::Htmx => write!(f, "htmx"),
+            ScaffoldKind::Model => write!(f, "model"),
+            ScaffoldKind::Controller => write!(f, "controller"),
+        }
+    }
+}
+
+// フレームワーク管理のカラムと、Locoが作成する型と矛盾しない型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedColumn {
+    pub name: String,
+    // 空なら型は確認しない
+    pub compatible_types: Vec<String>,
+}
+
+impl ManagedColumn {
+    pub fn new(name: &str, compatible_types: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            compatible_types: compatible_types.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    // Locoのscaffoldが必ず追加する `id` / `created_at` / `updated_at`
+    pub fn loco_defaults() -> Vec<Self> {
+        vec![
+            Self::new("id", &["int", "big_int", "integer", "serial"]),
+            Self::new("created_at", &["tstz", "date_time", "timestamp", "timestamptz", "datetime"]),
+            Self::new("updated_at", &["tstz", "date_time", "timestamp", "timestamptz", "datetime"]),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocoCommand {
+    pub command: String,
+    // scaffoldなどでモデルを新規作成する場合のモデル名
+    pub model: Option<String>,
+}
+
+impl Default for LocoGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl LocoGenerator {
     pub fn new() -> Self {
         Self {
-            generated_tables: HashSet::new(),
+            managed_columns: ManagedColumn::loco_defaults(),
+            scaffold_kind: ScaffoldKind::default(),
+            scaffold_overrides: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_scaffold_kind(mut self, kind: ScaffoldKind) -> Self {
+        self.scaffold_kind = kind;
+        self
+    }
+
+    pub fn with_scaffold_override(mut self, entity: &str, kind: ScaffoldKind) -> Self {
+        self.scaffold_overrides.insert(entity.to_string(), kind);
+        self
+    }
+
+    // 個別指定 → `scaffold=` 注釈 → 既定 の順に決める（解釈できない注釈は既定を使う）
+    pub fn scaffold_kind_for(&self, entity: &Entity) -> ScaffoldKind {
+        self.scaffold_overrides
+            .get(&entity.name)
+            .or_else(|| self.scaffold_overrides.get(&entity.model_name()))
+            .copied()
+            .or_else(|| entity.annotation("scaffold").and_then(|kind| kind.parse().ok()))
+            .unwrap_or(self.scaffold_kind)
+    }
+
+    pub fn with_managed_columns(mut self, managed_columns: Vec<ManagedColumn>) -> Self {
+        self.managed_columns = managed_columns;
+        self
+    }
+
+    fn managed_column(&self, attr: &Attribute) -> Option<&ManagedColumn> {
+        if attr.is_foreign_key {
+            return None;
         }
+        self.managed_columns.iter().find(|c| c.name == attr.name)
     }
     
     pub fn generate_commands(&self, schema: &Schema) -> Vec<String> {
+        self.generate_plan(schema).into_iter().map(|c| c.command).collect()
+    }
+
+    // 実行順に並べたコマンドと、それぞれが作成するモデル
+    pub fn generate_plan(&self, schema: &Schema) -> Vec<LocoCommand> {
+        // 1. 基本的なテーブル作成（scaffold）と、循環のため後回しにした外部キー
+        let mut commands = self.scaffold_plan(schema, |_| true);
+        // 2. 外部キー参照の追加
+        commands.extend(self.relationship_plan(schema, &schema.relationships.iter().collect::<Vec<_>>()));
+        commands
+    }
+
+    // 2つの図の差分だけを、既存のテーブルに対するコマンドにする
+    // 1. 追加されたエンティティのscaffold  2. 既存テーブルへのカラム・参照の追加
+    // 3. 追加されたリレーションシップ  4. カラムの削除  5. 削除されたテーブル（参照する側から）
+    pub fn generate_diff_plan(&self, diff: &SchemaDiff) -> Vec<LocoCommand> {
+        let schema = diff.new;
+        let added = |name: &str| diff.added_entities.iter().any(|e| e.name == name);
+        let mut commands = self.scaffold_plan(schema, added);
+
+        for (entity, attrs) in &diff.added_columns {
+            let attrs: Vec<&Attribute> = attrs
+                .iter()
+                .copied()
+                .filter(|a| !a.is_primary_key && self.managed_column(a).is_none())
+                .collect();
+            let columns: Vec<(&str, &str)> = attrs
+                .iter()
+                .filter(|a| !a.is_foreign_key)
+                .map(|a| (a.name.as_str(), a.data_type.as_str()))
+                .collect();
+            if !columns.is_empty() {
+                commands.push(LocoCommand {
+                    command: self.generate_add_column_command(&entity.table_name(), &columns),
+                    model: None,
+                });
+            }
+            for attr in attrs.iter().filter(|a| a.is_foreign_key) {
+                let parent = match schema.foreign_key_target(entity, attr) {
+                    Some(target) => target.model_name(),
+                    None => to_pascal_case(attr.name.strip_suffix("_id").unwrap_or(&attr.name)),
+                };
+                commands.push(LocoCommand {
+                    command: self.add_reference_command(schema, &parent, entity, attr),
+                    model: None,
+                });
+            }
+        }
+
+        commands.extend(self.relationship_plan(schema, &diff.added_relationships));
+
+        for (entity, attrs) in &diff.removed_columns {
+            let columns: Vec<(&str, &str)> = attrs
+                .iter()
+                .filter(|a| !a.is_primary_key && self.managed_column(a).is_none())
+                .map(|a| (a.name.as_str(), a.data_type.as_str()))
+                .collect();
+            if !columns.is_empty() {
+                commands.push(LocoCommand {
+                    command: self.generate_remove_column_command(&entity.table_name(), &columns),
+                    model: None,
+                });
+            }
+        }
+
+        // 削除されたテーブル同士の参照は、参照する側を先に削除する
+        let old_order = topological_order(diff.old).order;
+        for name in old_order.iter().rev() {
+            if let Some(entity) = diff.removed_entities.iter().find(|e| &e.name == name) {
+                commands.push(LocoCommand {
+                    command: format!("cargo loco generate migration Drop{}", to_pascal_case(&entity.table_name())),
+                    model: None,
+                });
+            }
+        }
+
+        commands
+    }
+
+    // `include` が真のエンティティのscaffoldを依存順に並べ、循環のため後回しにした外部キーを続ける
+    fn scaffold_plan(&self, schema: &Schema, include: impl Fn(&str) -> bool) -> Vec<LocoCommand> {
         let mut commands = Vec::new();
-        
-        // 1. 基本的なテーブル作成（scaffold）
-        for entity in &schema.entities {
-            commands.push(self.generate_scaffold_command(entity));
+        // 参照先のテーブルが先に作られるよう、外部キーの依存順に並べる
+        let dependencies = topological_order(schema);
+
+        for name in dependencies.order.iter().filter(|name| include(name)) {
+            let entity = schema.find_entity(name).expect("order only contains defined entities");
+            let deferred: HashSet<&str> = entity
+                .attributes
+                .iter()
+                .filter(|a| dependencies.is_deferred_foreign_key(&entity.name, &a.name))
+                .map(|a| a.name.as_str())
+                .collect();
+            let creates_model = self.scaffold_kind_for(entity).creates_model();
+            commands.push(LocoCommand {
+                command: self.build_scaffold_command(schema, entity, &deferred),
+                model: creates_model.then(|| entity.model_name()),
+            });
         }
-        
-        // 2. 外部キー参照の追加
-        for relationship in &schema.relationships {
-            match relationship.relationship_type {
-                RelationshipType::OneToMany => {
-                    commands.push(self.generate_reference_command(relationship));
+
+        // 循環のため後回しにした外部キー
+        for edge in dependencies.deferred.iter().filter(|edge| include(&edge.child)) {
+            if let EdgeSource::ForeignKey { attribute } = &edge.source {
+                let entity = schema.find_entity(&edge.child).expect("edges only reference defined entities");
+                let attr = entity.attributes.iter().find(|a| &a.name == attribute).expect("deferred attribute exists");
+                commands.push(LocoCommand {
+                    command: self.add_reference_command(schema, &schema.model_name_of(&edge.parent), entity, attr),
+                    model: None,
+                });
+            }
+        }
+
+        commands
+    }
+
+    // FK属性による参照を、テーブル作成後のマイグレーションで追加する
+    fn add_reference_command(&self, schema: &Schema, parent: &str, entity: &Entity, attr: &Attribute) -> String {
+        format!(
+            "cargo loco generate migration Add{}RefTo{} {}",
+            to_pascal_case(parent),
+            to_pascal_case(&entity.model_name()),
+            self.column_spec(schema, entity, attr)
+        )
+    }
+
+    // リレーションシップによる参照と中間テーブル
+    // 同じ参照を表すリレーションシップは1つにまとめ（同じ向きのものは `validate_duplicate_relationships` で警告する）、
+    // FK属性で既に作られる参照は追加しない
+    fn relationship_plan(&self, schema: &Schema, relationships: &[&Relationship]) -> Vec<LocoCommand> {
+        let mut commands = Vec::new();
+        let mut seen: HashSet<(&str, &str)> = HashSet::new();
+        for relationship in relationships {
+            let command = match relationship.relationship_type {
+                // 1対1も、省略可能な側（決まらなければ右側）が親を参照する
+                RelationshipType::OneToMany | RelationshipType::OneToOne => {
+                    let (parent, child) = relationship.parent_and_child().expect("one-to-many and one-to-one have a parent");
+                    if !seen.insert((parent, child)) || self.has_foreign_key_to(schema, child, parent) {
+                        continue;
+                    }
+                    self.generate_reference_command(schema, relationship)
                 }
                 RelationshipType::ManyToMany => {
-                    commands.push(self.generate_join_table_command(relationship));
+                    let (from, to) = (relationship.from_entity.as_str(), relationship.to_entity.as_str());
+                    if !seen.insert((from.min(to), from.max(to))) {
+                        continue;
+                    }
+                    Some(self.generate_join_table_command(schema, relationship))
                 }
-                _ => {}
-            }
+            };
+            commands.extend(command.map(|command| LocoCommand { command, model: None }));
         }
         
         commands
     }
     
+    // 単体のエンティティとしてscaffoldコマンドを作る（参照先は属性名から推測する）
     pub fn generate_scaffold_command(&self, entity: &Entity) -> String {
+        let schema = Schema {
+            entities: vec![entity.clone()],
+            relationships: Vec::new(),
+        };
+        self.build_scaffold_command(&schema, entity, &HashSet::new())
+    }
+
+    // `skip` に含まれる属性は、後続のマイグレーションで追加するためscaffoldから外す
+    fn build_scaffold_command(&self, schema: &Schema, entity: &Entity, skip: &HashSet<&str>) -> String {
+        let kind = self.scaffold_kind_for(entity);
+        let (generator, flag) = kind.command();
         let mut parts = vec![
             "cargo".to_string(),
             "loco".to_string(),
             "generate".to_string(),
-            "scaffold".to_string(),
-            entity.name.clone(),
+            generator.to_string(),
+            entity.model_name(),
         ];
         
-        // 外部キー以外の属性を追加
+        // 主キーとフレームワーク管理のカラム以外の属性を追加
         for attr in &entity.attributes {
-			if !attr.is_primary_key {
-				let loco_type = if attr.is_foreign_key {
-					"references".to_string()
-				} else {
-					LocoDataType::from_mermaid_type(&attr.data_type).to_loco_type().to_string()
-				};
-				parts.push(format!("{}:{}", attr.name, loco_type));
-			}
-		}
-		
+            let is_managed = self.managed_column(attr).is_some();
+            if kind.creates_model() && !attr.is_primary_key && !is_managed && !skip.contains(attr.name.as_str()) {
+                parts.push(self.column_spec(schema, entity, attr));
+            }
+        }
+        parts.extend(flag.map(str::to_string));
         
         parts.join(" ")
     }
+
+    // `name:type` 形式のカラム指定
+    // 外部キーは `_id` を除いた名前で `user:references` とし（Locoが `user_id` を作る）、
+    // カラム名と参照先テーブルが対応しない場合は `author:references:users` とする
+    fn column_spec(&self, schema: &Schema, entity: &Entity, attr: &Attribute) -> String {
+        if !attr.is_foreign_key {
+            let loco_type = LocoDataType::from_mermaid_type(&attr.data_type);
+            return format!("{}:{}", attr.name, loco_type);
+        }
+
+        let column = attr.name.strip_suffix("_id").unwrap_or(&attr.name);
+        match schema.foreign_key_target(entity, attr) {
+            Some(target) if to_snake_case(&target.model_name()) != column => {
+                format!("{}:references:{}", column, to_table_name(&target.model_name()))
+            }
+            _ => format!("{}:references", column),
+        }
+    }
     
-    fn generate_reference_command(&self, relationship: &Relationship) -> String {
-		let target_table = &relationship.from_entity; // 親側
-		let ref_name = target_table.to_lowercase();
-		let table_name = &relationship.to_entity; // 子側
-	
-		format!(
-			"cargo loco generate migration Add{}RefTo{} {}:references",
-			capitalize(target_table),
-			capitalize(table_name),
-			ref_name
-		)
-	}
-	
-    
-    fn generate_join_table_command(&self, relationship: &Relationship) -> String {
-		let table1 = &relationship.from_entity.to_lowercase();
-		let table2 = &relationship.to_entity.to_lowercase();
-		let table_name = format!("{}s_{}s", table1, table2); // 例: posts_tags
-	
-		format!(
-			"cargo loco generate migration CreateJoinTable{}And{} {}:references {}:references",
-			capitalize(table1),
-			capitalize(table2),
-			table1,
-			table2
-		)
-	}
-	
+    fn generate_reference_command(&self, schema: &Schema, relationship: &Relationship) -> Option<String> {
+        // 記述の向きではなくカーディナリティから親子を決める
+        let (parent, child) = relationship.parent_and_child()?;
+        let parent = schema.model_name_of(parent);
+        let child = schema.model_name_of(child);
+
+        Some(format!(
+            "cargo loco generate migration Add{}RefTo{} {}:references",
+            to_pascal_case(&parent),
+            to_pascal_case(&child),
+            to_snake_case(&parent)
+        ))
+    }
+
+    fn generate_join_table_command(&self, schema: &Schema, relationship: &Relationship) -> String {
+        let model1 = schema.model_name_of(&relationship.from_entity);
+        let model2 = schema.model_name_of(&relationship.to_entity);
+        format!(
+            "cargo loco generate migration CreateJoinTable{}And{} {}:references {}:references",
+            to_pascal_case(&model1),
+            to_pascal_case(&model2),
+            to_snake_case(&model1),
+            to_snake_case(&model2)
+        )
+    }
     
     // カラム追加のコマンドを生成
     pub fn generate_add_column_command(&self, table_name: &str, columns: &[(&str, &str)]) -> String {
-        let column_names: Vec<String> = columns.iter().map(|(name, _)| capitalize(name)).collect();
+        let column_names: Vec<String> = columns.iter().map(|(name, _)| to_pascal_case(name)).collect();
         let column_name_str = column_names.join("And");
         
         let mut parts = vec![
@@ -101,12 +401,12 @@ impl LocoGenerator {
             "loco".to_string(),
             "generate".to_string(),
             "migration".to_string(),
-            format!("Add{}To{}", column_name_str, capitalize(table_name)),
+            format!("Add{}To{}", column_name_str, to_pascal_case(table_name)),
         ];
         
         for (name, data_type) in columns {
             let loco_type = LocoDataType::from_mermaid_type(data_type);
-            parts.push(format!("{}:{}", name, loco_type.to_loco_type()));
+            parts.push(format!("{}:{}", name, loco_type));
         }
         
         parts.join(" ")
@@ -114,7 +414,7 @@ impl LocoGenerator {
     
     // カラム削除のコマンドを生成
     pub fn generate_remove_column_command(&self, table_name: &str, columns: &[(&str, &str)]) -> String {
-        let column_names: Vec<String> = columns.iter().map(|(name, _)| capitalize(name)).collect();
+        let column_names: Vec<String> = columns.iter().map(|(name, _)| to_pascal_case(name)).collect();
         let column_name_str = column_names.join("And");
         
         let mut parts = vec![
@@ -122,40 +422,251 @@ impl LocoGenerator {
             "loco".to_string(),
             "generate".to_string(),
             "migration".to_string(),
-            format!("Remove{}From{}", column_name_str, capitalize(table_name)),
+            format!("Remove{}From{}", column_name_str, to_pascal_case(table_name)),
         ];
         
         for (name, data_type) in columns {
             let loco_type = LocoDataType::from_mermaid_type(data_type);
-            parts.push(format!("{}:{}", name, loco_type.to_loco_type()));
+            parts.push(format!("{}:{}", name, loco_type));
         }
         
         parts.join(" ")
     }
 
-	pub fn validate_relationship_entities(&self,schema: &Schema) {
-		let entity_names:  HashSet<_> = schema.entities.iter().map(|e| e.name.as_str()).collect();
-		for rel in &schema.relationships {
-			if !entity_names.contains(rel.from_entity.as_str()) {
-				eprintln!(
-                    "⚠️ Warning: from_entity '{}' not found in entities",
-                    rel.from_entity
+    // 子エンティティが親を参照するFK属性を持っているか
+    fn has_foreign_key_to(&self, schema: &Schema, child: &str, parent: &str) -> bool {
+        schema.find_entity(child).is_some_and(|entity| {
+            entity
+                .attributes
+                .iter()
+                .filter(|a| a.is_foreign_key)
+                .any(|a| schema.foreign_key_target(entity, a).is_some_and(|t| t.name == parent))
+        })
+    }
+
+    // FK属性とリレーションシップが食い違っていないか確認する
+    pub fn validate_foreign_keys(&self, schema: &Schema) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for entity in &schema.entities {
+            for attr in entity.attributes.iter().filter(|a| a.is_foreign_key) {
+                let column = format!("{}.{}", entity.name, attr.name);
+                let Some(target) = schema.foreign_key_target(entity, attr) else {
+                    diagnostics.push(
+                        Diagnostic::warning(format!("{} の参照先エンティティが見つかりません", column))
+                            .with_help("`%% triton: references=<エンティティ名>` で参照先を指定してください"),
+                    );
+                    continue;
+                };
+                if target.name == entity.name {
+                    continue;
+                }
+
+                let between: Vec<&Relationship> = schema
+                    .relationships
+                    .iter()
+                    .filter(|r| {
+                        (r.from_entity == entity.name && r.to_entity == target.name)
+                            || (r.from_entity == target.name && r.to_entity == entity.name)
+                    })
+                    .collect();
+                if between.is_empty() {
+                    diagnostics.push(
+                        Diagnostic::warning(format!(
+                            "{} は {} を参照していますが、リレーションシップが記述されていません",
+                            column, target.name
+                        ))
+                        .with_help(format!("`{} ||--o{{ {} : \"...\"` のようなリレーションシップを追加してください", target.name, entity.name)),
+                    );
+                } else if !between
+                    .iter()
+                    .any(|r| r.parent_and_child() == Some((target.name.as_str(), entity.name.as_str())))
+                {
+                    let relationship = between[0];
+                    diagnostics.push(
+                        Diagnostic::warning(format!(
+                            "{} は {} を参照していますが、リレーションシップでは {} が参照する側になっていません",
+                            column, target.name, entity.name
+                        ))
+                        .with_span(relationship.span.clone())
+                        .with_help("リレーションシップのカーディナリティか、FK属性の位置を見直してください"),
+                    );
+                }
+            }
+        }
+        diagnostics
+    }
+
+    // フレームワーク管理のカラムが、Locoの作成する型と異なる型で宣言されていないか確認する
+    pub fn validate_managed_columns(&self, schema: &Schema) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for entity in &schema.entities {
+            for attr in &entity.attributes {
+                let Some(managed) = self.managed_column(attr) else {
+                    continue;
+                };
+                // Mermaid上の型名とLocoの型名のどちらかが一致すれば矛盾なしとする
+                let declared = attr.data_type.to_lowercase();
+                let loco_type = LocoDataType::from_mermaid_type(&attr.data_type);
+                let candidates = [declared.trim_end_matches(['!', '^']), loco_type.base.name()];
+                let compatible = managed.compatible_types.is_empty()
+                    || managed.compatible_types.iter().any(|t| candidates.contains(&t.as_str()));
+                if !compatible {
+                    diagnostics.push(
+                        Diagnostic::warning(format!(
+                            "{}.{} は {} 型で宣言されていますが、Locoは {} 型で作成します",
+                            entity.name, attr.name, attr.data_type, managed.compatible_types[0]
+                        ))
+                        .with_help("このカラムはLocoが自動で作成するため、scaffoldからは除外されます"),
+                    );
+                }
+            }
+        }
+        diagnostics
+    }
+
+    // `scaffold=` 注釈の値と、個別指定したエンティティ名が正しいか確認する
+    pub fn validate_scaffold_kinds(&self, schema: &Schema) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for entity in &schema.entities {
+            if let Some(Err(message)) = entity.annotation("scaffold").map(str::parse::<ScaffoldKind>) {
+                diagnostics.push(
+                    Diagnostic::warning(format!("{} の scaffold 注釈: {}", entity.name, message))
+                        .with_help(format!("既定の生成方法（{}）を使います", self.scaffold_kind)),
                 );
-			}
-			if !entity_names.contains(rel.to_entity.as_str()) {
-                eprintln!(
-                    "⚠️ Warning: to_entity '{}' not found in entities",
-                    rel.to_entity
+            }
+            // コントローラだけではテーブルが作られないため、参照に含まれていれば参照先が無くなる
+            let referenced = schema.relationships.iter().any(|r| r.from_entity == entity.name || r.to_entity == entity.name)
+                || entity.attributes.iter().any(|a| a.is_foreign_key)
+                || schema.entities.iter().any(|other| {
+                    other
+                        .attributes
+                        .iter()
+                        .any(|a| a.is_foreign_key && schema.foreign_key_target(other, a).is_some_and(|t| t.name == entity.name))
+                });
+            if !self.scaffold_kind_for(entity).creates_model() && referenced {
+                diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "{} は controller として生成するためテーブルを作成しませんが、参照に含まれています",
+                        entity.name
+                    ))
+                    .with_help("参照を持つエンティティには api・html・htmx・model のいずれかを指定してください"),
                 );
             }
-		}
-	}
-}
+        }
+        for name in self.scaffold_overrides.keys() {
+            let defined = schema.entities.iter().any(|e| &e.name == name || &e.model_name() == name);
+            if !defined {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "生成方法を指定したエンティティ '{}' は定義されていません",
+                    name
+                )));
+            }
+        }
+        diagnostics
+    }
+
+    // 名前・表示名がASCIIの英数字を含まず、モデル名を作れないエンティティが無いか確認する
+    pub fn validate_model_names(&self, schema: &Schema) -> Vec<Diagnostic> {
+        schema
+            .entities
+            .iter()
+            .filter(|entity| !entity.has_model_name())
+            .map(|entity| {
+                Diagnostic::error(format!("エンティティ '{}' の名前からモデル名を作れません", entity.name))
+                    .with_help(format!(
+                        "`u[\"{}\"]` のように、ASCIIの英数字の識別子を付けてください",
+                        entity.alias.as_deref().unwrap_or(&entity.name)
+                    ))
+            })
+            .collect()
+    }
 
-fn capitalize(s: &str) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    // `table=` 注釈はLocoのコマンドでは指定できない（Locoはモデル名からテーブル名を決める）
+    pub fn validate_table_annotations(&self, schema: &Schema) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for entity in &schema.entities {
+            let Some(table) = entity.annotation("table") else {
+                continue;
+            };
+            let loco_table = to_table_name(&entity.model_name());
+            if table != loco_table {
+                diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "{} の table 注釈 '{}' はLocoのコマンドには反映されません",
+                        entity.name, table
+                    ))
+                    .with_help(format!(
+                        "Locoはモデル名からテーブル '{}' を作成します（`--emit migration`・`entities`・`sql` では注釈のテーブル名を使います）",
+                        loco_table
+                    )),
+                );
+            }
+        }
+        diagnostics
     }
-}
\ No newline at end of file
+
+    // 同じエンティティの組を同じ向きで結ぶリレーションシップが複数ないか確認する
+    // 参照・中間テーブルは組ごとに1つしか作らないため、2つ目以降は生成されない
+    // 逆向きに書かれたものは、同じリレーションシップを相手側から記述したものとみなす
+    pub fn validate_duplicate_relationships(&self, schema: &Schema) -> Vec<Diagnostic> {
+        let key = |r: &Relationship| match r.relationship_type {
+            RelationshipType::ManyToMany => {
+                let (from, to) = (r.from_entity.as_str(), r.to_entity.as_str());
+                (true, from.min(to).to_string(), from.max(to).to_string())
+            }
+            _ => {
+                let (parent, child) = r.parent_and_child().expect("one-to-many and one-to-one have a parent");
+                (false, parent.to_string(), child.to_string())
+            }
+        };
+
+        let mut diagnostics = Vec::new();
+        for (i, relationship) in schema.relationships.iter().enumerate() {
+            let (is_many_to_many, parent, child) = key(relationship);
+            let duplicated = schema.relationships[..i].iter().any(|r| {
+                r.from_entity == relationship.from_entity
+                    && r.to_entity == relationship.to_entity
+                    && key(r) == (is_many_to_many, parent.clone(), child.clone())
+            });
+            if !duplicated || (!is_many_to_many && self.has_foreign_key_to(schema, &child, &parent)) {
+                continue;
+            }
+            let help = if is_many_to_many {
+                format!("{} と {} の中間テーブルは1つだけ作成します", parent, child)
+            } else {
+                format!(
+                    "参照は1つ（{}.{}_id）だけ生成します。別々の参照が必要な場合は、FK属性と `%% triton: references=` で参照カラムを記述してください",
+                    child,
+                    to_snake_case(&schema.model_name_of(&parent))
+                )
+            };
+            diagnostics.push(
+                Diagnostic::warning(format!(
+                    "{} と {} のリレーションシップが同じ向きで複数記述されています",
+                    relationship.from_entity, relationship.to_entity
+                ))
+                .with_span(relationship.span.clone())
+                .with_help(help),
+            );
+        }
+        diagnostics
+    }
+
+    // リレーションシップが存在しないエンティティを参照していないか確認する
+    pub fn validate_relationship_entities(&self, schema: &Schema) -> Vec<Diagnostic> {
+        let entity_names: HashSet<_> = schema.entities.iter().map(|e| e.name.as_str()).collect();
+        let mut diagnostics = Vec::new();
+        for rel in &schema.relationships {
+            for name in [&rel.from_entity, &rel.to_entity] {
+                if !entity_names.contains(name.as_str()) {
+                    diagnostics.push(
+                        Diagnostic::warning(format!("エンティティ '{}' が定義されていません", name))
+                            .with_span(rel.span.as_ref().map(|span| Span::find(span.line, &span.snippet, name)))
+                            .with_help(format!("エンティティ '{}' を定義するか、名前の綴りを確認してください", name)),
+                    );
+                }
+            }
+        }
+        diagnostics
+    }
+}
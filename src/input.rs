@@ -0,0 +1,189 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::{DataTypeError, Span};
+use crate::markdown;
+use crate::types::{Constraint, Entity, LocoDataType, Schema};
+use std::fmt;
+use std::str::FromStr;
+
+// 入力ファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Mermaid,
+    // ```mermaid で囲まれた erDiagram ブロックを含むMarkdown
+    Markdown,
+    // `--emit json` と同じ形のスキーマ
+    Json,
+    Yaml,
+}
+
+impl InputFormat {
+    // 拡張子から形式を決める（不明ならMermaid）
+    pub fn from_path(path: &str) -> Self {
+        let lower = path.to_lowercase();
+        if markdown::is_markdown_path(path) {
+            InputFormat::Markdown
+        } else if lower.ends_with(".json") {
+            InputFormat::Json
+        } else if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+            InputFormat::Yaml
+        } else {
+            InputFormat::Mermaid
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mermaid" => Ok(InputFormat::Mermaid),
+            "markdown" | "md" => Ok(InputFormat::Markdown),
+            "json" => Ok(InputFormat::Json),
+            "yaml" | "yml" => Ok(InputFormat::Yaml),
+            _ => Err(format!("'{}' は入力形式ではありません", s)),
+        }
+    }
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputFormat::Mermaid => write!(f, "mermaid"),
+            InputFormat::Markdown => write!(f, "markdown"),
+            InputFormat::Json => write!(f, "json"),
+            InputFormat::Yaml => write!(f, "yaml"),
+        }
+    }
+}
+
+// `--emit json` と同じ形のJSONを読み込む（構文エラーは位置付きの診断にする）
+pub fn parse_json(content: &str) -> Result<Schema, Diagnostic> {
+    serde_json::from_str(content)
+        .map_err(|e| load_error(content, "JSON", e.to_string(), Some((e.line(), e.column()))))
+}
+
+// JSONと同じ構造のYAMLを読み込む
+pub fn parse_yaml(content: &str) -> Result<Schema, Diagnostic> {
+    serde_yaml_ng::from_str(content).map_err(|e| {
+        let location = e.location().map(|l| (l.line(), l.column()));
+        load_error(content, "YAML", e.to_string(), location)
+    })
+}
+
+fn load_error(content: &str, format: &str, message: String, location: Option<(usize, usize)>) -> Diagnostic {
+    let span = location.and_then(|(line, column)| {
+        let snippet = content.lines().nth(line.checked_sub(1)?)?;
+        Some(Span {
+            line,
+            column: column.max(1),
+            length: 1,
+            snippet: snippet.to_string(),
+        })
+    });
+    Diagnostic::error(format!("{}のスキーマとして読み込めません: {}", format, message))
+        .with_span(span)
+        .with_help("`triton <file> --emit json` で出力される形式で記述してください")
+}
+
+// 手書きのスキーマの型名を、Mermaidと同じ規則（別名・引数付きの型）でLocoの型に揃える
+// 変換できない型は `string` として扱い、診断を返す
+pub fn normalize_data_types(schema: &mut Schema, severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for entity in &mut schema.entities {
+        for attr in &mut entity.attributes {
+            let mut reported = false;
+            let mut normalized = match LocoDataType::parse(&attr.data_type) {
+                Ok(data_type) => data_type,
+                Err(error) => {
+                    reported = true;
+                    let message = match &error {
+                        DataTypeError::UnknownType(_) => format!(
+                            "{}.{} の型 '{}' はLocoの型に変換できません",
+                            entity.name, attr.name, attr.data_type
+                        ),
+                        DataTypeError::UnsupportedConstraint(_) => format!(
+                            "{}.{} の型 '{}' にはユニーク制約を指定できません",
+                            entity.name, attr.name, attr.data_type
+                        ),
+                        _ => format!("{}.{}: {}", entity.name, attr.name, error),
+                    };
+                    diagnostics.push(Diagnostic::error(message).with_severity(severity));
+                    LocoDataType::from_mermaid_type(&attr.data_type)
+                }
+            };
+            // is_unique / is_nullable のフラグも型の修飾子と合わせて1つの制約にまとめる
+            if attr.is_unique && normalized.constraint != Constraint::Unique {
+                if normalized.base.supports_unique() {
+                    normalized.constraint = Constraint::Unique;
+                } else {
+                    if !reported {
+                        diagnostics.push(
+                            Diagnostic::error(format!(
+                                "{}.{} の型 '{}' にはユニーク制約を指定できません",
+                                entity.name, attr.name, attr.data_type
+                            ))
+                            .with_severity(severity),
+                        );
+                    }
+                    normalized.constraint = Constraint::Required;
+                }
+            }
+            if attr.is_nullable && normalized.constraint != Constraint::Nullable {
+                diagnostics.push(
+                    Diagnostic::error(format!(
+                        "{}.{} は is_nullable ですが型 '{}' はnullを許容しません（型の修飾子を使います）",
+                        entity.name, attr.name, attr.data_type
+                    ))
+                    .with_severity(severity),
+                );
+            }
+            attr.data_type = normalized.to_string();
+            attr.is_unique = normalized.constraint == Constraint::Unique;
+            // Mermaidのパーサーと同じく、修飾子もキーも無いカラムをnull許容とする
+            attr.is_nullable = normalized.constraint == Constraint::Nullable
+                && !attr.is_primary_key
+                && !attr.is_foreign_key;
+        }
+    }
+    diagnostics
+}
+
+// Mermaidと同じく、同じ名前のエンティティは1つにまとめる（複数のブロック・ファイルに分けて書かれた場合など）
+// 後の定義にだけある属性・注釈を最初の定義に加え、同じ属性の定義が食い違えば最初の定義を使う
+pub fn merge_duplicate_entities(schema: &mut Schema, severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut merged: Vec<Entity> = Vec::new();
+    for entity in std::mem::take(&mut schema.entities) {
+        let Some(first) = merged.iter_mut().find(|e| e.name == entity.name) else {
+            merged.push(entity);
+            continue;
+        };
+        diagnostics.push(
+            Diagnostic::warning(format!("エンティティ '{}' が複数回定義されています", entity.name))
+                .with_severity(severity)
+                .with_help("属性をまとめて1つのエンティティとして扱います"),
+        );
+        if first.alias.is_none() {
+            first.alias = entity.alias;
+        }
+        for (key, value) in entity.annotations {
+            first.annotations.entry(key).or_insert(value);
+        }
+        for attr in entity.attributes {
+            match first.attributes.iter().find(|a| a.name == attr.name) {
+                Some(existing) if existing != &attr => diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "{}.{} の定義が食い違っています（最初の定義 '{}' を使います）",
+                        first.name, attr.name, existing.data_type
+                    ))
+                    .with_severity(severity),
+                ),
+                Some(_) => {}
+                None => first.attributes.push(attr),
+            }
+        }
+    }
+    schema.entities = merged;
+    diagnostics
+}
@@ -1,7 +1,24 @@
+pub mod dependency;
+pub mod diagnostic;
+pub mod diff;
+pub mod entities;
+pub mod error;
+pub mod input;
+pub mod migration;
 pub mod parser;
+pub mod script;
+pub mod sql;
 pub mod generator;
+pub mod markdown;
+pub mod naming;
 pub mod types;
 
+pub use diagnostic::{Diagnostic, DiagnosticReporter, Severity};
+pub use diff::SchemaDiff;
+pub use entities::{EntityFile, EntityGenerator};
+pub use error::{DataTypeError, ParseError, ParseErrors, Span};
 pub use parser::MermaidParser;
-pub use generator::LocoGenerator;
+pub use sql::{Dialect, SqlGenerator};
+pub use generator::{LocoCommand, LocoGenerator, ManagedColumn, ScaffoldKind};
+pub use migration::{MigrationFile, MigrationGenerator};
 pub use types::*;
\ No newline at end of file
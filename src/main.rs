@@ -1,48 +1,419 @@
 use std::env;
 use std::fs;
-use std::io::{self, Write};
+use std::path::Path;
 
-mod parser;
-mod generator;
-mod types;
+use triton::dependency;
+use triton::entities::EntityGenerator;
+use triton::diagnostic::{Diagnostic, DiagnosticReporter, Severity};
+use triton::diff::SchemaDiff;
+use triton::generator::{LocoGenerator, ManagedColumn, ScaffoldKind};
+use triton::input::{self, InputFormat};
+use triton::markdown::{self, BlockSelector};
+use triton::migration::{self, MigrationGenerator};
+use triton::parser::MermaidParser;
+use triton::script;
+use triton::sql::{Dialect, SqlGenerator};
+use triton::types::Schema;
 
-use parser::MermaidParser;
-use generator::LocoGenerator;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Emit {
+    // 1行1コマンド
+    Commands,
+    // そのまま実行できるbashスクリプト
+    Script,
+    // 解析したスキーマ（他のツールから読むためのJSON）
+    Json,
+    // Locoプロジェクトの migration/src に直接書き出すマイグレーションファイル
+    Migration,
+    // Locoプロジェクトの src/models/_entities に書き出すSeaORMのエンティティ
+    Entities,
+    // データベースごとの `CREATE TABLE` 文
+    Sql,
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
-    
-    if args.len() < 2 {
-        eprintln!("使用方法: {} <mermaid_file_path> [output_file_path]", args[0]);
+    let mut strict = false;
+    let mut block: Option<BlockSelector> = None;
+    let mut emit = Emit::Commands;
+    let mut managed_columns: Option<Vec<ManagedColumn>> = None;
+    let mut from: Option<InputFormat> = None;
+    let mut scaffold_kind: Option<ScaffoldKind> = None;
+    let mut scaffold_overrides: Vec<(String, ScaffoldKind)> = Vec::new();
+    let mut dialect = Dialect::default();
+    let mut positional = Vec::new();
+
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            // 解釈できない行があればエラーにする（CI向け）
+            "--strict" => strict = true,
+            // Markdown内の erDiagram ブロックをインデックスか見出しで選ぶ
+            "--block" => match iter.next() {
+                Some(value) => block = Some(BlockSelector::parse(value)),
+                None => {
+                    eprintln!("--block にはブロックのインデックスか見出しを指定してください");
+                    std::process::exit(1);
+                }
+            },
+            // 出力形式: commands（既定）/ script / json / migration / entities / sql
+            "--emit" => match iter.next().map(String::as_str) {
+                Some("commands") => emit = Emit::Commands,
+                Some("script") => emit = Emit::Script,
+                Some("json") => emit = Emit::Json,
+                Some("migration") => emit = Emit::Migration,
+                Some("entities") => emit = Emit::Entities,
+                Some("sql") => emit = Emit::Sql,
+                _ => {
+                    eprintln!("--emit には commands・script・json・migration・entities・sql のいずれかを指定してください");
+                    std::process::exit(1);
+                }
+            },
+            // 入力形式: 未指定なら拡張子から決める
+            "--from" => match iter.next().map(|v| v.parse::<InputFormat>()) {
+                Some(Ok(format)) => from = Some(format),
+                _ => {
+                    eprintln!("--from には mermaid・markdown・json・yaml のいずれかを指定してください");
+                    std::process::exit(1);
+                }
+            },
+            // `--emit sql` のデータベース: postgres（既定）/ sqlite / mysql
+            "--dialect" => match iter.next().map(|v| v.parse::<Dialect>()) {
+                Some(Ok(value)) => dialect = value,
+                _ => {
+                    eprintln!("--dialect には postgres・sqlite・mysql のいずれかを指定してください");
+                    std::process::exit(1);
+                }
+            },
+            // 生成方法: `--scaffold html` で既定を、`--scaffold Tag=model` でエンティティごとに指定する
+            "--scaffold" => {
+                let parsed = iter.next().map(|value| match value.split_once('=') {
+                    Some((entity, kind)) => kind.parse().map(|kind| scaffold_overrides.push((entity.to_string(), kind))),
+                    None => value.parse().map(|kind| scaffold_kind = Some(kind)),
+                });
+                match parsed {
+                    Some(Ok(())) => {}
+                    Some(Err(message)) => {
+                        eprintln!("--scaffold: {}", message);
+                        std::process::exit(1);
+                    }
+                    None => {
+                        eprintln!("--scaffold には api・html・htmx・model・controller か、<エンティティ名>=<生成方法> を指定してください");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            // scaffoldから除外するフレームワーク管理のカラム（カンマ区切り、空文字で無効化）
+            "--managed-columns" => match iter.next() {
+                Some(value) => managed_columns = Some(parse_managed_columns(value)),
+                None => {
+                    eprintln!("--managed-columns には除外するカラム名をカンマ区切りで指定してください");
+                    std::process::exit(1);
+                }
+            },
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    // `triton diff old.mermaid new.mermaid` は2つの図の差分だけをコマンドにする
+    let diff = positional.first() == Some(&"diff");
+    if diff {
+        positional.remove(0);
+    }
+
+    if positional.is_empty() || (diff && positional.len() < 2) {
+        eprintln!(
+            "使用方法: {0} [--strict] [--block <index|heading>] [--emit commands|script|json|migration|entities|sql] [--dialect postgres|sqlite|mysql] [--scaffold <kind|Entity=kind>] [--managed-columns <names>] [--from mermaid|markdown|json|yaml] <input_file_path> [output_file_path]\n          {0} diff [--emit commands|script] <old_file_path> <new_file_path> [output_file_path]",
+            args[0]
+        );
+        std::process::exit(1);
+    }
+
+    if diff && !matches!(emit, Emit::Commands | Emit::Script) {
+        eprintln!("diff では --emit に commands か script を指定してください");
+        std::process::exit(1);
+    }
+
+    let (old_file, input_file, output_file) = if diff {
+        (Some(positional[0]), positional[1], positional.get(2).copied())
+    } else {
+        (None, positional[0], positional.get(1).copied())
+    };
+
+    let mut generator = LocoGenerator::new();
+    let mut migration_generator = MigrationGenerator::new();
+    let mut entity_generator = EntityGenerator::new();
+    let mut sql_generator = SqlGenerator::new(dialect);
+    if let Some(managed_columns) = managed_columns {
+        sql_generator = sql_generator.with_managed_columns(managed_columns.clone());
+        entity_generator = entity_generator.with_managed_columns(managed_columns.clone());
+        generator = generator.with_managed_columns(managed_columns.clone());
+        migration_generator = migration_generator.with_managed_columns(managed_columns);
+    }
+    if let Some(kind) = scaffold_kind {
+        generator = generator.with_scaffold_kind(kind);
+    }
+    for (entity, kind) in &scaffold_overrides {
+        generator = generator.with_scaffold_override(entity, *kind);
+    }
+    let severity = if strict { Severity::Error } else { Severity::Warning };
+
+    // 差分の元になる図は解析の問題だけを報告する
+    let old_schema = match old_file {
+        Some(old_file) => {
+            let mut reporter = DiagnosticReporter::new(old_file);
+            let schema = load_schema(old_file, from, block.as_ref(), severity, &mut reporter)?;
+            if !reporter.diagnostics().is_empty() {
+                eprint!("{}", reporter.render());
+            }
+            if reporter.has_errors() {
+                std::process::exit(1);
+            }
+            Some(schema)
+        }
+        None => None,
+    };
+
+    let mut reporter = DiagnosticReporter::new(input_file);
+    let schema = load_schema(input_file, from, block.as_ref(), severity, &mut reporter)?;
+    let schema_diff = old_schema.as_ref().map(|old| SchemaDiff::between(old, &schema));
+    reporter.extend(generator.validate_model_names(&schema));
+    reporter.extend(generator.validate_relationship_entities(&schema));
+    reporter.extend(generator.validate_foreign_keys(&schema));
+    reporter.extend(generator.validate_duplicate_relationships(&schema));
+    reporter.extend(generator.validate_managed_columns(&schema));
+    reporter.extend(generator.validate_scaffold_kinds(&schema));
+    reporter.extend(dependency::topological_order(&schema).diagnostics());
+    if let Some(schema_diff) = &schema_diff {
+        reporter.extend(schema_diff.diagnostics());
+    }
+    if emit == Emit::Sql {
+        reporter.extend(sql_generator.diagnostics(&schema));
+    }
+    if matches!(emit, Emit::Commands | Emit::Script) {
+        reporter.extend(generator.validate_table_annotations(&schema));
+    }
+
+    if !reporter.diagnostics().is_empty() {
+        eprint!("{}", reporter.render());
+    }
+    if reporter.has_errors() {
         std::process::exit(1);
     }
-    
-    let input_file = &args[1];
-    let output_file = args.get(2).map(|s| s.as_str());
-    
-    // Mermaidファイルを読み込み
-    let mermaid_content = fs::read_to_string(input_file)?;
-    
-    // パース
-    let mut parser = MermaidParser::new();
-    let schema = parser.parse(&mermaid_content)?;
-    
-    // Locoコマンド生成
-    let generator = LocoGenerator::new();
-    let commands = generator.generate_commands(&schema);
-    
+
+    // マイグレーションは出力先をLocoプロジェクトのルートとして書き出す
+    if emit == Emit::Migration {
+        return write_migrations(&migration_generator, &schema, Path::new(output_file.unwrap_or(".")));
+    }
+    if emit == Emit::Entities {
+        return write_entities(&entity_generator, &schema, Path::new(output_file.unwrap_or(".")));
+    }
+
     // 出力
-    let output = commands.join("\n");
-    
+    let plan = match &schema_diff {
+        Some(schema_diff) => generator.generate_diff_plan(schema_diff),
+        None => generator.generate_plan(&schema),
+    };
+    let output = match emit {
+        Emit::Commands => plan
+            .iter()
+            .map(|c| c.command.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Emit::Script => script::render_script(&plan, input_file),
+        Emit::Json => serde_json::to_string_pretty(&schema)?,
+        Emit::Sql => sql_generator.generate(&schema),
+        Emit::Migration | Emit::Entities => unreachable!("ファイルは書き出し済み"),
+    };
+
     match output_file {
         Some(path) => {
             fs::write(path, &output)?;
-            println!("コマンドを {}に出力しました", path);
+            if emit == Emit::Script {
+                make_executable(path)?;
+            }
+            match emit {
+                Emit::Json => println!("スキーマを {}に出力しました", path),
+                Emit::Sql => println!("DDLを {}に出力しました", path),
+                _ => println!("コマンドを {}に出力しました", path),
+            }
         }
         None => {
             println!("{}", output);
         }
     }
-    
+
+    Ok(())
+}
+
+// 入力ファイルを読み込み、形式ごとに解析する（問題のある行も全て集めてから報告する）
+fn load_schema(
+    path: &str,
+    from: Option<InputFormat>,
+    block: Option<&BlockSelector>,
+    severity: Severity,
+    reporter: &mut DiagnosticReporter,
+) -> std::io::Result<Schema> {
+    let content = fs::read_to_string(path)?;
+    let format = from.unwrap_or_else(|| InputFormat::from_path(path));
+    let mut schema = match format {
+        InputFormat::Mermaid => parse_diagram(&content, 0, severity, reporter),
+        InputFormat::Markdown => parse_markdown(&content, block, severity, reporter),
+        InputFormat::Json | InputFormat::Yaml => parse_schema_file(&content, format, severity, reporter),
+    };
+    reporter.extend(input::merge_duplicate_entities(&mut schema, severity));
+    Ok(schema)
+}
+
+fn parse_diagram(
+    content: &str,
+    line_offset: usize,
+    severity: Severity,
+    reporter: &mut DiagnosticReporter,
+) -> Schema {
+    let mut parser = MermaidParser::new().with_line_offset(line_offset);
+    // lenientモードのパースは失敗しないため、問題は診断として報告する
+    let schema = parser.parse(content).expect("lenient mode never fails");
+    reporter.extend(parser.problems().iter().map(|p| Diagnostic::from_parse_error(p, severity)));
+    schema
+}
+
+// Markdown内の erDiagram ブロックだけを解析し、選択されたブロック（未指定なら全て）をまとめる
+fn parse_markdown(
+    content: &str,
+    block: Option<&BlockSelector>,
+    severity: Severity,
+    reporter: &mut DiagnosticReporter,
+) -> Schema {
+    let mut schema = Schema {
+        entities: Vec::new(),
+        relationships: Vec::new(),
+    };
+    let blocks: Vec<_> = markdown::extract_er_diagrams(content)
+        .into_iter()
+        .filter(|b| block.is_none_or(|selector| selector.matches(b)))
+        .collect();
+
+    if blocks.is_empty() {
+        reporter.push(
+            Diagnostic::error("erDiagram を含む mermaid ブロックが見つかりません")
+                .with_help("```mermaid で囲んだ erDiagram を記述するか、--block の指定を確認してください"),
+        );
+    }
+
+    for block in blocks {
+        let parsed = parse_diagram(&block.content, block.line_offset, severity, reporter);
+        schema.entities.extend(parsed.entities);
+        schema.relationships.extend(parsed.relationships);
+    }
+
+    schema
+}
+
+// Locoの既定カラムは型の確認を引き継ぎ、それ以外は名前だけで除外する
+fn parse_managed_columns(value: &str) -> Vec<ManagedColumn> {
+    let defaults = ManagedColumn::loco_defaults();
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            defaults
+                .iter()
+                .find(|c| c.name == name)
+                .cloned()
+                .unwrap_or_else(|| ManagedColumn::new(name, &[]))
+        })
+        .collect()
+}
+
+// JSON・YAMLで書かれたスキーマを読み込み、型名をMermaidと同じ規則で揃える
+fn parse_schema_file(
+    content: &str,
+    format: InputFormat,
+    severity: Severity,
+    reporter: &mut DiagnosticReporter,
+) -> Schema {
+    let loaded = match format {
+        InputFormat::Yaml => input::parse_yaml(content),
+        _ => input::parse_json(content),
+    };
+    match loaded {
+        Ok(mut schema) => {
+            reporter.extend(input::normalize_data_types(&mut schema, severity));
+            schema
+        }
+        Err(diagnostic) => {
+            reporter.push(diagnostic);
+            Schema {
+                entities: Vec::new(),
+                relationships: Vec::new(),
+            }
+        }
+    }
+}
+
+// 同じ処理のマイグレーション（`m..._create_users` の日時以外が一致するもの）が既にあれば書き出さない
+fn write_migrations(
+    generator: &MigrationGenerator,
+    schema: &Schema,
+    project_root: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let migration_dir = project_root.join("migration/src");
+    fs::create_dir_all(&migration_dir)?;
+    let existing: Vec<String> = fs::read_dir(&migration_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+
+    let mut written = Vec::new();
+    for file in generator.generate(schema) {
+        let action = migration::migration_action(&file.module);
+        if let Some(found) = existing
+            .iter()
+            .find(|name| name.strip_suffix(".rs").map(migration::migration_action) == Some(action))
+        {
+            println!("skip: migration/src/{} が既に存在します", found);
+            continue;
+        }
+        fs::write(project_root.join(file.path()), &file.content)?;
+        println!("{} を作成しました", file.path());
+        written.push(file.module);
+    }
+
+    let lib_path = migration_dir.join("lib.rs");
+    let lib_rs = fs::read_to_string(&lib_path).ok();
+    match migration::register_migrations(lib_rs.as_deref(), &written) {
+        Ok(updated) => fs::write(&lib_path, updated)?,
+        Err(diagnostic) => {
+            let mut reporter = DiagnosticReporter::new(lib_path.display().to_string());
+            reporter.push(diagnostic);
+            eprint!("{}", reporter.render());
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+// エンティティはスキーマから再生成するものなので、既存のファイルは上書きする
+fn write_entities(generator: &EntityGenerator, schema: &Schema, project_root: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(project_root.join("src/models/_entities"))?;
+    for file in generator.generate(schema) {
+        fs::write(project_root.join(file.path()), &file.content)?;
+        println!("{} を作成しました", file.path());
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &str) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &str) -> std::io::Result<()> {
     Ok(())
 }
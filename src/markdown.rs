@@ -0,0 +1,115 @@
+// Markdown中の ```mermaid フェンスから erDiagram を取り出す
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MermaidBlock {
+    // 文書内で何番目の erDiagram ブロックか（0始まり）
+    pub index: usize,
+    // 直前にある見出しのテキスト（`#` を除く）
+    pub heading: Option<String>,
+    // フェンス内の本文
+    pub content: String,
+    // 本文1行目より前にある行数（診断の行番号をMarkdownの行に合わせるため）
+    pub line_offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockSelector {
+    Index(usize),
+    Heading(String),
+}
+
+impl BlockSelector {
+    // 数値ならインデックス、それ以外は見出しとして扱う
+    pub fn parse(value: &str) -> Self {
+        match value.parse::<usize>() {
+            Ok(index) => BlockSelector::Index(index),
+            Err(_) => BlockSelector::Heading(value.to_string()),
+        }
+    }
+
+    pub fn matches(&self, block: &MermaidBlock) -> bool {
+        match self {
+            BlockSelector::Index(index) => block.index == *index,
+            BlockSelector::Heading(heading) => block
+                .heading
+                .as_deref()
+                .is_some_and(|h| h.eq_ignore_ascii_case(heading.trim())),
+        }
+    }
+}
+
+pub fn is_markdown_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".markdown")
+}
+
+// erDiagram を含む mermaid フェンスを全て取り出す
+pub fn extract_er_diagrams(markdown: &str) -> Vec<MermaidBlock> {
+    let mut blocks = Vec::new();
+    let mut heading: Option<String> = None;
+    // (閉じフェンス, 情報文字列がmermaidか, 本文開始行, 本文)
+    let mut fence: Option<(String, bool, usize, Vec<&str>)> = None;
+
+    for (index, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim();
+
+        match fence.as_mut() {
+            Some((marker, is_mermaid, start, body)) => {
+                if is_closing_fence(trimmed, marker) {
+                    if *is_mermaid && body.iter().any(|l| l.trim_start().starts_with("erDiagram")) {
+                        blocks.push(MermaidBlock {
+                            index: blocks.len(),
+                            heading: heading.clone(),
+                            content: body.join("\n"),
+                            line_offset: *start,
+                        });
+                    }
+                    fence = None;
+                } else {
+                    body.push(line);
+                }
+            }
+            None => {
+                if let Some(marker) = fence_marker(trimmed) {
+                    let info = trimmed[marker.len()..].trim();
+                    let is_mermaid = info.split_whitespace().next() == Some("mermaid");
+                    fence = Some((marker, is_mermaid, index + 1, Vec::new()));
+                } else if let Some(text) = atx_heading(trimmed) {
+                    heading = Some(text.to_string());
+                }
+            }
+        }
+    }
+
+    blocks
+}
+
+// ``` や ~~~ で始まる行ならフェンス記号を返す
+fn fence_marker(line: &str) -> Option<String> {
+    for fence_char in ['`', '~'] {
+        let count = line.chars().take_while(|&c| c == fence_char).count();
+        if count >= 3 {
+            return Some(fence_char.to_string().repeat(count));
+        }
+    }
+    None
+}
+
+// 開始と同じ記号で、同じ長さ以上の記号だけの行が閉じフェンス
+fn is_closing_fence(line: &str, marker: &str) -> bool {
+    match fence_marker(line) {
+        Some(closing) => closing.starts_with(marker) && line[closing.len()..].trim().is_empty(),
+        None => false,
+    }
+}
+
+// `## 見出し` 形式の見出しならテキストを返す
+fn atx_heading(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches('#');
+    let level = line.len() - rest.len();
+    if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+        Some(rest.trim().trim_end_matches('#').trim())
+    } else {
+        None
+    }
+}
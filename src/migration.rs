@@ -0,0 +1,462 @@
+use crate::dependency::{topological_order, EdgeSource};
+use crate::diagnostic::Diagnostic;
+use crate::generator::ManagedColumn;
+use crate::naming::{pluralize, to_snake_case};
+use crate::types::*;
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Locoの `create_table` ヘルパーを使ったSeaORMのマイグレーションファイル
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationFile {
+    // `m20250101_000001_create_users` のようなモジュール名
+    pub module: String,
+    pub content: String,
+}
+
+impl MigrationFile {
+    // Locoプロジェクトのルートからの相対パス
+    pub fn path(&self) -> String {
+        format!("migration/src/{}.rs", self.module)
+    }
+}
+
+// `cargo loco generate` を実行せずに、同じSchemaからマイグレーションのソースを直接作る
+pub struct MigrationGenerator {
+    // `create_table` が自動で作るため列に含めないカラム
+    managed_columns: Vec<ManagedColumn>,
+    // 最初のマイグレーションの時刻（UNIX秒）。以降は1秒ずつずらして実行順を保つ
+    timestamp: u64,
+}
+
+impl Default for MigrationGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// `create_table` に渡す参照: (参照先, 参照名)
+// 参照名が空ならLocoが参照先の単数形から `user_id` のようなカラムを作る
+type Reference = (String, String);
+
+impl MigrationGenerator {
+    pub fn new() -> Self {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Self {
+            managed_columns: ManagedColumn::loco_defaults(),
+            timestamp: now,
+        }
+    }
+
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn with_managed_columns(mut self, managed_columns: Vec<ManagedColumn>) -> Self {
+        self.managed_columns = managed_columns;
+        self
+    }
+
+    // 依存順に並べたマイグレーション
+    // 1. テーブル作成（外部キーとリレーションシップの参照を含む）
+    // 2. 循環のため後回しにした参照
+    // 3. 多対多の中間テーブル
+    pub fn generate(&self, schema: &Schema) -> Vec<MigrationFile> {
+        let dependencies = topological_order(schema);
+        let mut files = Vec::new();
+
+        for name in &dependencies.order {
+            let entity = schema.find_entity(name).expect("order only contains defined entities");
+            let references: Vec<Reference> = foreign_keys(schema, entity)
+                .iter()
+                .filter(|fk| !dependencies.deferred.iter().any(|edge| edge.child == entity.name && edge.source == fk.source))
+                .map(ForeignKey::reference)
+                .collect();
+            let module = self.module_name(files.len(), &format!("create_{}", entity.table_name()));
+            files.push(MigrationFile {
+                module,
+                content: self.render_create_table(entity, &references),
+            });
+        }
+
+        for edge in &dependencies.deferred {
+            let child = schema.find_entity(&edge.child).expect("edges only reference defined entities");
+            let parent = schema.find_entity(&edge.parent).expect("edges only reference defined entities");
+            let Some(foreign_key) = foreign_keys(schema, child).into_iter().find(|fk| fk.source == edge.source) else {
+                continue;
+            };
+            let (target, ref_name) = foreign_key.reference();
+            let module = self.module_name(
+                files.len(),
+                &format!("add_{}_ref_to_{}", to_snake_case(&parent.model_name()), child.table_name()),
+            );
+            files.push(MigrationFile {
+                module,
+                content: render_add_reference(&child.table_name(), &target, &ref_name),
+            });
+        }
+
+        for join_table in join_tables(schema) {
+            let from = to_snake_case(&schema.model_name_of(&join_table.from));
+            let to = to_snake_case(&schema.model_name_of(&join_table.to));
+            let module = self.module_name(files.len(), &format!("create_join_table_{}_and_{}", from, to));
+            files.push(MigrationFile {
+                module,
+                content: render_join_table(&join_table.table, &[(from, String::new()), (to, String::new())]),
+            });
+        }
+
+        files
+    }
+
+    fn module_name(&self, index: usize, name: &str) -> String {
+        format!("m{}_{}", format_timestamp(self.timestamp + index as u64), name)
+    }
+
+    fn render_create_table(&self, entity: &Entity, references: &[Reference]) -> String {
+        let table = entity.table_name();
+        let primary_key = if entity.has_uuid_primary_key() { "PkUuid" } else { "PkAuto" };
+        let mut columns = vec![format!("(\"id\", ColType::{})", primary_key)];
+        for attr in &entity.attributes {
+            let is_managed = self.managed_columns.iter().any(|c| c.name == attr.name);
+            if !attr.is_primary_key && !attr.is_foreign_key && !is_managed {
+                let data_type = LocoDataType::from_mermaid_type(&attr.data_type);
+                columns.push(format!("(\"{}\", ColType::{})", attr.name, col_type(&data_type)));
+            }
+        }
+
+        let body = format!(
+            "        create_table(\n            m,\n            \"{}\",\n{},\n{},\n        )\n        .await",
+            table,
+            render_list(&columns, 12),
+            render_list(&render_references(references), 12),
+        );
+        render_migration(&body, &format!("drop_table(m, \"{}\").await", table))
+    }
+}
+
+// `migration/src/lib.rs` にモジュールと `Box::new(...::Migration)` を追加する
+// 既に登録されているモジュールは追加しない。lib.rs が無ければ新しく作る
+pub fn register_migrations(lib_rs: Option<&str>, modules: &[String]) -> Result<String, Diagnostic> {
+    let Some(lib_rs) = lib_rs else {
+        return Ok(render_lib_rs(modules));
+    };
+    let mut lines: Vec<String> = lib_rs.lines().map(str::to_string).collect();
+
+    for module in modules {
+        let declaration = format!("mod {};", module);
+        if lines.iter().any(|l| l.trim() == declaration) {
+            continue;
+        }
+
+        // `mod` 宣言は既存のマイグレーションの後ろ（無ければ `pub struct Migrator;` の前）
+        let insert_at = lines
+            .iter()
+            .rposition(|l| l.trim_start().starts_with("mod m"))
+            .map(|i| i + 1)
+            .or_else(|| lines.iter().position(|l| l.contains("pub struct Migrator")))
+            .ok_or_else(|| unrecognized_lib_rs("`pub struct Migrator;`"))?;
+        lines.insert(insert_at, declaration);
+
+        // Locoの `// inject-above` の目印か、`vec![` を閉じる行の前に追加する
+        let vec_start = lines
+            .iter()
+            .position(|l| l.contains("vec!["))
+            .ok_or_else(|| unrecognized_lib_rs("`fn migrations()` の `vec![`"))?;
+        let anchor = lines[vec_start..]
+            .iter()
+            .position(|l| l.contains("// inject-above"))
+            .or_else(|| lines[vec_start..].iter().position(|l| l.trim_start().starts_with(']')))
+            .map(|i| vec_start + i)
+            .ok_or_else(|| unrecognized_lib_rs("`vec![` を閉じる `]`"))?;
+        let indent = lines[anchor.saturating_sub(1)..=anchor]
+            .iter()
+            .find(|l| l.contains("Box::new") || l.contains("// inject-above"))
+            .map_or("            ".to_string(), |l| l[..l.len() - l.trim_start().len()].to_string());
+        lines.insert(anchor, format!("{}Box::new({}::Migration),", indent, module));
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+// 日時を除いたマイグレーションの処理名（`m20250101_000001_create_users` → `create_users`）
+pub fn migration_action(module: &str) -> &str {
+    module.splitn(3, '_').nth(2).unwrap_or(module)
+}
+
+// テーブルが持つ外部キー
+#[derive(Debug, Clone)]
+pub struct ForeignKey<'a> {
+    // `user_id` のようなカラム名
+    pub column: String,
+    // 参照先（FK属性の参照先が見つからない場合は None）
+    pub target: Option<&'a Entity>,
+    pub source: EdgeSource,
+    // 1対1のリレーションシップによる参照（参照先から見ると has_one になる）
+    pub is_one_to_one: bool,
+}
+
+impl ForeignKey<'_> {
+    // `create_table` に渡す参照（カラム名と参照先が対応しない場合は参照名を指定する）
+    fn reference(&self) -> Reference {
+        let column = self.column.strip_suffix("_id").unwrap_or(&self.column);
+        match self.target {
+            Some(target) if to_snake_case(&target.model_name()) != column => (target.table_name(), column.to_string()),
+            _ => (column.to_string(), String::new()),
+        }
+    }
+}
+
+// FK属性と、FK属性の無い1対多・1対1のリレーションシップから外部キーを集める
+// FK属性は `_id` を除いた名前に `_id` を付けたカラムになる（Locoの `references` と同じ）
+pub fn foreign_keys<'a>(schema: &'a Schema, entity: &Entity) -> Vec<ForeignKey<'a>> {
+    let mut foreign_keys = Vec::new();
+    let mut parents: HashSet<String> = HashSet::new();
+    let relationship_to = |parent: &str| {
+        schema
+            .relationships
+            .iter()
+            .find(|r| r.parent_and_child() == Some((parent, entity.name.as_str())))
+    };
+
+    for attr in entity.attributes.iter().filter(|a| a.is_foreign_key) {
+        let target = schema.foreign_key_target(entity, attr);
+        if let Some(target) = target {
+            parents.insert(target.name.clone());
+        }
+        let relationship = target.and_then(|t| relationship_to(&t.name));
+        foreign_keys.push(ForeignKey {
+            column: format!("{}_id", attr.name.strip_suffix("_id").unwrap_or(&attr.name)),
+            target,
+            source: EdgeSource::ForeignKey { attribute: attr.name.clone() },
+            is_one_to_one: relationship.is_some_and(|r| r.relationship_type == RelationshipType::OneToOne),
+        });
+    }
+
+    for relationship in &schema.relationships {
+        let Some((parent, child)) = relationship.parent_and_child() else {
+            continue;
+        };
+        let Some(target) = schema.find_entity(parent) else {
+            continue;
+        };
+        if child != entity.name || !parents.insert(parent.to_string()) {
+            continue;
+        }
+        foreign_keys.push(ForeignKey {
+            column: format!("{}_id", to_snake_case(&target.model_name())),
+            target: Some(target),
+            source: EdgeSource::Relationship { span: relationship.span.clone() },
+            is_one_to_one: relationship.relationship_type == RelationshipType::OneToOne,
+        });
+    }
+
+    foreign_keys
+}
+
+// 多対多のリレーションシップの中間テーブル
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinTable {
+    // `post_tags` のようなテーブル名
+    pub table: String,
+    pub from: String,
+    pub to: String,
+}
+
+// 同じ組み合わせの多対多は1つにまとめる
+pub fn join_tables(schema: &Schema) -> Vec<JoinTable> {
+    let mut seen: HashSet<(&str, &str)> = HashSet::new();
+    let mut join_tables = Vec::new();
+    for relationship in &schema.relationships {
+        let (from, to) = (relationship.from_entity.as_str(), relationship.to_entity.as_str());
+        if relationship.relationship_type != RelationshipType::ManyToMany || !seen.insert((from.min(to), from.max(to))) {
+            continue;
+        }
+        join_tables.push(JoinTable {
+            table: format!(
+                "{}_{}",
+                to_snake_case(&schema.model_name_of(from)),
+                pluralize(&to_snake_case(&schema.model_name_of(to)))
+            ),
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+    join_tables
+}
+
+fn unrecognized_lib_rs(missing: &str) -> Diagnostic {
+    Diagnostic::error(format!("migration/src/lib.rs に {} が見つかりません", missing))
+        .with_help("`cargo loco new` で作成されたLocoプロジェクトの migration/src/lib.rs を指定してください")
+}
+
+// Locoの `ColType` のバリアント名
+// 無印はnull許容（`Null`）、`!` は必須、`^` はユニーク（`Uniq`）
+pub fn col_type(data_type: &LocoDataType) -> String {
+    let data_type = data_type.fill_missing_params();
+    let stem = match data_type.base {
+        LocoBaseType::Uuid => "Uuid",
+        LocoBaseType::String => "String",
+        LocoBaseType::Text => "Text",
+        LocoBaseType::SmallUnsigned => "SmallUnsigned",
+        LocoBaseType::BigUnsigned => "BigUnsigned",
+        LocoBaseType::SmallInt => "SmallInteger",
+        LocoBaseType::Int => "Integer",
+        LocoBaseType::BigInt => "BigInteger",
+        LocoBaseType::Float => "Float",
+        LocoBaseType::Double => "Double",
+        LocoBaseType::Decimal => "Decimal",
+        LocoBaseType::DecimalLen => "DecimalLen",
+        LocoBaseType::Bool => "Boolean",
+        LocoBaseType::Tstz => "TimestampWithTimeZone",
+        LocoBaseType::Date => "Date",
+        LocoBaseType::DateTime => "DateTime",
+        LocoBaseType::Blob => "Blob",
+        LocoBaseType::Json => "Json",
+        LocoBaseType::JsonBinary => "JsonBinary",
+        LocoBaseType::Money => "Money",
+        LocoBaseType::Unsigned => "Unsigned",
+        LocoBaseType::BinaryLen => "BinaryLen",
+        LocoBaseType::VarBinary => "VarBinary",
+        LocoBaseType::Array => "Array",
+    };
+    let (stem, args) = match (data_type.base, data_type.params) {
+        (_, Some(TypeParams::Precision { precision, scale })) => (stem, format!("({}, {})", precision, scale)),
+        (_, Some(TypeParams::Length(length))) => (stem, format!("({})", length)),
+        (_, Some(TypeParams::Element(element))) => (stem, format!("(ArrayColType::{})", array_col_type(element))),
+        // LocoのVarBinaryは長さが必要なため、長さの指定が無い可変長のバイナリはBlobにする
+        (LocoBaseType::VarBinary, None) => ("Blob", String::new()),
+        _ => (stem, String::new()),
+    };
+    let suffix = match data_type.constraint {
+        Constraint::Nullable => "Null",
+        Constraint::Required => "",
+        Constraint::Unique => "Uniq",
+    };
+    format!("{}{}{}", stem, suffix, args)
+}
+
+fn array_col_type(element: LocoBaseType) -> &'static str {
+    match element {
+        LocoBaseType::Int => "Int",
+        LocoBaseType::Float => "Float",
+        LocoBaseType::Bool => "Bool",
+        _ => "String",
+    }
+}
+
+fn render_references(references: &[Reference]) -> Vec<String> {
+    references
+        .iter()
+        .map(|(target, ref_name)| format!("(\"{}\", \"{}\")", target, ref_name))
+        .collect()
+}
+
+// `&[...]` を1要素1行で書く（空なら `&[]`）
+fn render_list(items: &[String], indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    if items.is_empty() {
+        return format!("{}&[]", pad);
+    }
+    let mut lines = vec![format!("{}&[", pad)];
+    lines.extend(items.iter().map(|item| format!("{}    {},", pad, item)));
+    lines.push(format!("{}]", pad));
+    lines.join("\n")
+}
+
+fn render_join_table(table: &str, references: &[Reference]) -> String {
+    let body = format!(
+        "        create_join_table(\n            m,\n            \"{}\",\n            &[],\n{},\n        )\n        .await",
+        table,
+        render_list(&render_references(references), 12),
+    );
+    render_migration(&body, &format!("drop_table(m, \"{}\").await", table))
+}
+
+fn render_add_reference(table: &str, target: &str, ref_name: &str) -> String {
+    render_migration(
+        &format!("        add_reference(m, \"{}\", \"{}\", \"{}\").await", table, target, ref_name),
+        &format!("remove_reference(m, \"{}\", \"{}\", \"{}\").await", table, target, ref_name),
+    )
+}
+
+fn render_migration(up: &str, down: &str) -> String {
+    format!(
+        r#"use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {{
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {{
+{}
+    }}
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {{
+        {}
+    }}
+}}
+"#,
+        up, down
+    )
+}
+
+fn render_lib_rs(modules: &[String]) -> String {
+    let declarations: Vec<String> = modules.iter().map(|m| format!("mod {};", m)).collect();
+    let migrations: Vec<String> = modules
+        .iter()
+        .map(|m| format!("            Box::new({}::Migration),", m))
+        .collect();
+    format!(
+        r#"#![allow(elided_lifetimes_in_paths)]
+#![allow(clippy::wildcard_imports)]
+pub use sea_orm_migration::prelude::*;
+
+{}
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {{
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {{
+        vec![
+{}
+            // inject-above (do not remove this comment)
+        ]
+    }}
+}}
+"#,
+        declarations.join("\n"),
+        migrations.join("\n")
+    )
+}
+
+// UNIX秒を `20250101_093000` の形式にする（UTC）
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // 1970-01-01 からの日数を年月日に変換する
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}_{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
@@ -0,0 +1,147 @@
+// モデル名・カラム名の変換ヘルパー
+
+pub fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+// Rustの識別子として使える名前か（英字か `_` で始まり、英数字と `_` のみ）
+pub fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+// 空白・記号・大文字の境目で単語に分ける
+fn split_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = s.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        // `UserProfile` や `HTTPRequest` の境目
+        let boundary = c.is_ascii_uppercase()
+            && !current.is_empty()
+            && (chars[i - 1].is_ascii_lowercase()
+                || chars[i - 1].is_ascii_digit()
+                || chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase()));
+        if boundary {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+// `Order Item` / `order_item` → `OrderItem`
+pub fn to_pascal_case(s: &str) -> String {
+    split_words(s)
+        .iter()
+        .map(|w| capitalize(&w.to_lowercase()))
+        .collect()
+}
+
+// `OrderItem` / `Order Item` → `order_item`
+pub fn to_snake_case(s: &str) -> String {
+    split_words(s)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+// 複数形が不規則な単語（Locoが使うInflectorの規則に合わせ、語末が一致すれば置き換える）
+const IRREGULAR_PLURALS: &[(&str, &str)] = &[
+    ("person", "people"),
+    ("man", "men"),
+    ("child", "children"),
+    ("mouse", "mice"),
+    ("louse", "lice"),
+    ("quiz", "quizzes"),
+    ("matrix", "matrices"),
+    ("vertex", "vertices"),
+    ("index", "indices"),
+    ("octopus", "octopi"),
+    ("virus", "viri"),
+    ("buffalo", "buffaloes"),
+    ("tomato", "tomatoes"),
+];
+
+// 複数形も同じ形の単語
+const UNCOUNTABLE_WORDS: &[&str] = &[
+    "equipment",
+    "information",
+    "rice",
+    "money",
+    "species",
+    "series",
+    "fish",
+    "sheep",
+    "deer",
+    "news",
+    "police",
+];
+
+// 英語の複数形（Locoのテーブル名・モデルファイル名に合わせる）
+// `order_item` のように複数の単語からなる場合は最後の単語だけを複数形にする
+pub fn pluralize(s: &str) -> String {
+    let lower = s.to_lowercase();
+    let last_word = lower.rsplit('_').next().unwrap_or(&lower);
+    let stem = |suffix: &str| &s[..s.len() - suffix.len()];
+
+    if UNCOUNTABLE_WORDS.contains(&last_word) {
+        return s.to_string();
+    }
+    if last_word == "ox" {
+        return format!("{}en", s);
+    }
+    if let Some((singular, plural)) = IRREGULAR_PLURALS.iter().find(|(singular, _)| last_word.ends_with(singular)) {
+        return format!("{}{}", stem(singular), plural);
+    }
+
+    // `category` → `categories`（`day` → `days` のように母音+yはそのまま）
+    let consonant_y = lower.ends_with('y')
+        && (lower.ends_with("quy")
+            || lower[..lower.len() - 1]
+                .chars()
+                .last()
+                .is_some_and(|c| !"aeiou".contains(c)));
+    // `knife` → `knives`、`shelf` → `shelves`
+    let f_to_ves = (lower.ends_with("fe") && !lower.ends_with("ffe"))
+        || lower.ends_with("lf")
+        || lower.ends_with("rf");
+    if consonant_y {
+        format!("{}ies", stem("y"))
+    } else if f_to_ves {
+        format!("{}ves", stem(if lower.ends_with("fe") { "fe" } else { "f" }))
+    } else if lower.ends_with("sis") {
+        format!("{}ses", stem("sis"))
+    } else if lower.ends_with("tum") || lower.ends_with("ium") {
+        format!("{}a", stem("um"))
+    } else if ["s", "x", "z", "ch", "sh"].iter().any(|suffix| lower.ends_with(suffix)) {
+        format!("{}es", s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+// `OrderItem` → `order_items`
+pub fn to_table_name(model_name: &str) -> String {
+    pluralize(&to_snake_case(model_name))
+}
@@ -1,79 +1,199 @@
+use crate::error::{DataTypeError, ParseError, ParseErrors, Span};
 use crate::types::*;
-use regex::Regex;
-use std::collections::HashMap;
+use regex::{Captures, Regex};
+
+// 属性1つ分の記述
+// 型名の後ろの `!` は必須、`^` はユニークを表す
+// `decimal_len(10,2)` / `binary_len(16)` / `array<string>` のように引数を付けられる
+const ATTRIBUTE_PATTERN: &str = r#"
+    (?P<type>\w+)(?P<modifier>[!^])?(?P<params>\([^)]*\)|<[^>]*>)?(?P<post_modifier>[!^])?\s+
+    (?P<name>\w+)\s*
+    (?P<keys>(?:PK|FK|UK)(?:\s*,?\s*(?:PK|FK|UK))*\b)?\s*(?P<nullable>\?)?\s*
+    (?:"(?P<comment>[^"]*)")?
+"#;
 
 pub struct MermaidParser {
     entity_regex: Regex,
     attribute_regex: Regex,
+    // `Tag { string name }` のように1行に並べた属性
+    inline_attribute_regex: Regex,
     relationship_regex: Regex,
+    // trueなら解釈できない行が1つでもあればエラーにする
+    strict: bool,
+    // 直近のparseで見つかった問題（lenientモードでは警告として扱う）
+    problems: Vec<ParseError>,
+    // Markdown内のブロックなど、入力の先頭より前にある行数
+    line_offset: usize,
+}
+
+impl Default for MermaidParser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MermaidParser {
     pub fn new() -> Self {
         Self {
-            entity_regex: Regex::new(r"^\s*(\w+)\s*\{").unwrap(),
-            attribute_regex: Regex::new(r"^\s*(\w+)\s+(\w+)\s*(PK)?\s*(FK)?\s*(\?)?\s*$").unwrap(),
+            // `User {` / `"Order Item" {` / `p[Person] {` / `p["Person Name"] {`
+            entity_regex: Regex::new(
+                r#"(?x)
+                    ^\s*(?:(?P<name>\w+)|"(?P<quoted_name>[^"]+)")\s*
+                    (?:\[\s*(?:(?P<alias>\w+)|"(?P<quoted_alias>[^"]+)")\s*\])?\s*\{
+                "#
+            ).unwrap(),
+            attribute_regex: Regex::new(&format!(r"(?x)^\s*{}\s*$", ATTRIBUTE_PATTERN)).unwrap(),
+            inline_attribute_regex: Regex::new(&format!(r"(?x)^\s*{}(?:\s+|$)", ATTRIBUTE_PATTERN)).unwrap(),
+            // Mermaidのカーディナリティ記法（記号・別名の両方）と `--` / `..` の線種
             relationship_regex: Regex::new(
-				r#"(?x)
-					(?P<from_entity>\w+)\s*
-					(?P<symbol>\|\|--\|\||\|\|--o\{|\}o--\|\||\}o--o\{|
-								 \|o--\|\||o\|--o\{|\|o--o\{|\}o--o\||o\|--\|\|)\s*
-					(?P<to_entity>\w+)\s*:\s*
-					"(?P<label>.+)"
-				"#
-			).unwrap(),
+                r#"(?x)
+                    ^\s*(?:(?P<from_entity>\w+)|"(?P<from_quoted>[^"]+)")\s*
+                    (?P<from_card>\|o|\|\||\}o|\}\||\b(?:one\s+or\s+zero|zero\s+or\s+one|only\s+one|
+                        zero\s+or\s+more|zero\s+or\s+many|one\s+or\s+more|one\s+or\s+many|
+                        many\(0\)|many\(1\)|0\+|1\+|1))
+                    \s*(?P<line>--|\.\.|optionally\s+to|to)\s*
+                    (?P<to_card>o\||\|\||o\{|\|\{|\b(?:one\s+or\s+zero|zero\s+or\s+one|only\s+one|
+                        zero\s+or\s+more|zero\s+or\s+many|one\s+or\s+more|one\s+or\s+many|
+                        many\(0\)|many\(1\)|0\+|1\+|1))
+                    \s*(?:(?P<to_entity>\w+)|"(?P<to_quoted>[^"]+)")\s*:\s*
+                    (?:"(?P<label>[^"]*)"|(?P<bare_label>\S+))\s*$
+                "#
+            ).unwrap(),
+            strict: false,
+            problems: Vec::new(),
+            line_offset: 0,
         }
     }
+
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    // 診断の行番号を元ファイルの行に合わせる
+    pub fn with_line_offset(mut self, line_offset: usize) -> Self {
+        self.line_offset = line_offset;
+        self
+    }
+
+    // 直近のparseで無視した行
+    pub fn problems(&self) -> &[ParseError] {
+        &self.problems
+    }
     
-    pub fn parse(&mut self, content: &str) -> Result<Schema, Box<dyn std::error::Error>> {
+    pub fn parse(&mut self, content: &str) -> Result<Schema, ParseErrors> {
         let mut entities = Vec::new();
         let mut relationships = Vec::new();
-        let mut current_entity: Option<Entity> = None;
+        let mut current_entity: Option<(Entity, Span)> = None;
+        // 次のエンティティ・属性に付ける `%% triton:` 注釈
+        let mut pending_annotations: Option<(Annotations, Span)> = None;
+        self.problems.clear();
         
-        for line in content.lines() {
-            let line = line.trim();
+        for (index, raw_line) in content.lines().enumerate() {
+            let line_no = self.line_offset + index + 1;
+            let line = raw_line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+            // `%%` コメントと `%%{init: ...}%%` ディレクティブは読み飛ばす
+            if let Some(comment) = line.strip_prefix("%%") {
+                if let Some(hints) = comment.trim().strip_prefix("triton:") {
+                    let (annotations, _) = pending_annotations
+                        .get_or_insert_with(|| (Annotations::new(), Span::from_line(line_no, raw_line)));
+                    annotations.extend(parse_annotations(hints));
+                }
+                continue;
+            }
+            let mut annotations = pending_annotations.take();
             
             // エンティティの開始
             if let Some(captures) = self.entity_regex.captures(line) {
-                if let Some(entity) = current_entity.take() {
+                if let Some((entity, span)) = current_entity.take() {
+                    self.problems.push(ParseError::UnclosedEntity { entity: entity.name.clone(), span });
                     entities.push(entity);
                 }
                 
-                let entity_name = captures.get(1).unwrap().as_str().to_string();
-                current_entity = Some(Entity {
+                let entity_name = captures
+                    .name("name")
+                    .or_else(|| captures.name("quoted_name"))
+                    .unwrap()
+                    .as_str()
+                    .to_string();
+                let alias = captures
+                    .name("alias")
+                    .or_else(|| captures.name("quoted_alias"))
+                    .map(|m| m.as_str().to_string());
+                let mut entity = Entity {
                     name: entity_name,
+                    alias,
                     attributes: Vec::new(),
-                });
+                    annotations: annotations.take().map(|(a, _)| a).unwrap_or_default(),
+                };
+                // `{` の後ろに書かれた属性も読み、`Tag {}` / `Tag { string name }` のように同じ行で閉じていればエンティティを終える
+                let rest = &line[captures.get(0).unwrap().end()..];
+                let close = rest.find('}');
+                let mut inline = rest[..close.unwrap_or(rest.len())].trim();
+                while !inline.is_empty() {
+                    let Some(attr_captures) = self.inline_attribute_regex.captures(inline) else {
+                        self.problems.push(ParseError::InvalidAttribute {
+                            entity: entity.name.clone(),
+                            span: Span::find(line_no, raw_line, inline),
+                        });
+                        break;
+                    };
+                    let attribute = self.parse_attribute(&entity, &attr_captures, inline, line_no, raw_line, None);
+                    entity.attributes.push(attribute);
+                    inline = inline[attr_captures.get(0).unwrap().end()..].trim_start();
+                }
+                match close {
+                    Some(close) => {
+                        let trailing = rest[close + 1..].trim();
+                        if !trailing.is_empty() {
+                            self.problems.push(ParseError::UnrecognizedLine {
+                                span: Span::find(line_no, raw_line, trailing),
+                            });
+                        }
+                        entities.push(entity);
+                    }
+                    None => current_entity = Some((entity, Span::find(line_no, raw_line, "{"))),
+                }
             }
             // エンティティ内の属性
-            else if let Some(ref mut entity) = current_entity {
+            else if let Some((ref mut entity, _)) = current_entity {
                 if line == "}" {
                     entities.push(entity.clone());
                     current_entity = None;
                 } else if let Some(captures) = self.attribute_regex.captures(line) {
-                    let raw_type = captures.get(1).unwrap().as_str();
-					let attr_type = LocoDataType::from_mermaid_type(raw_type).to_loco_type().to_string();
-					let attr_name = captures.get(2).unwrap().as_str().to_string();
-					let is_pk = captures.get(3).is_some();
-					let is_fk = captures.get(4).is_some();
-					let is_nullable = captures.get(5).is_some();
-                    
-                    entity.attributes.push(Attribute {
-                        name: attr_name,
-                        data_type: attr_type,
-                        is_primary_key: is_pk,
-                        is_foreign_key: is_fk,
-                        is_nullable,
+                    let attribute = self.parse_attribute(entity, &captures, line, line_no, raw_line, annotations.take().map(|(a, _)| a));
+                    entity.attributes.push(attribute);
+                } else {
+                    self.problems.push(ParseError::InvalidAttribute {
+                        entity: entity.name.clone(),
+                        span: Span::from_line(line_no, raw_line),
                     });
                 }
             }
             // リレーションシップ
             else if let Some(captures) = self.relationship_regex.captures(line) {
-                let from_entity = captures.get(1).unwrap().as_str().to_string();
-                let relation_symbol = captures.get(2).unwrap().as_str();
-                let to_entity = captures.get(3).unwrap().as_str().to_string();
-                
-                let (rel_type, from_card, to_card) = self.parse_relationship_symbol(relation_symbol);
+                let from_entity = captures
+                    .name("from_entity")
+                    .or_else(|| captures.name("from_quoted"))
+                    .unwrap()
+                    .as_str()
+                    .to_string();
+                let to_entity = captures
+                    .name("to_entity")
+                    .or_else(|| captures.name("to_quoted"))
+                    .unwrap()
+                    .as_str()
+                    .to_string();
+                let from_card = parse_cardinality(captures.name("from_card").unwrap().as_str());
+                let to_card = parse_cardinality(captures.name("to_card").unwrap().as_str());
+                // `--` / `to` は識別関係、`..` / `optionally to` は非識別関係
+                let line_kind = captures.name("line").unwrap().as_str();
+                let is_identifying = line_kind == "--" || line_kind == "to";
+                let rel_type = RelationshipType::from_cardinalities(&from_card, &to_card);
                 
                 relationships.push(Relationship {
                     from_entity,
@@ -81,25 +201,179 @@ impl MermaidParser {
                     relationship_type: rel_type,
                     from_cardinality: from_card,
                     to_cardinality: to_card,
+                    is_identifying,
+                    span: Some(Span::from_line(line_no, raw_line)),
+                });
+            }
+            // ヘッダ以外は解釈できない行として記録
+            else if line != "erDiagram" {
+                self.problems.push(ParseError::UnrecognizedLine {
+                    span: Span::from_line(line_no, raw_line),
                 });
             }
+
+            // 注釈の直後がエンティティ・属性ではなかった
+            if let Some((_, span)) = annotations {
+                self.problems.push(ParseError::DanglingAnnotation { span });
+            }
+        }
+        if let Some((_, span)) = pending_annotations {
+            self.problems.push(ParseError::DanglingAnnotation { span });
         }
         
         // 最後のエンティティを追加
-        if let Some(entity) = current_entity {
+        if let Some((entity, span)) = current_entity {
+            self.problems.push(ParseError::UnclosedEntity { entity: entity.name.clone(), span });
             entities.push(entity);
         }
+
+        if self.strict && !self.problems.is_empty() {
+            return Err(ParseErrors(self.problems.clone()));
+        }
         
         Ok(Schema { entities, relationships })
     }
-    
-    fn parse_relationship_symbol(&self, symbol: &str) -> (RelationshipType, Cardinality, Cardinality) {
-        match symbol {
-            "||--||" => (RelationshipType::OneToOne, Cardinality::One, Cardinality::One),
-            "||--o{" => (RelationshipType::OneToMany, Cardinality::One, Cardinality::ZeroOrMany),
-            "o{--||" => (RelationshipType::OneToMany, Cardinality::ZeroOrMany, Cardinality::One),
-            "o{--o{" => (RelationshipType::ManyToMany, Cardinality::ZeroOrMany, Cardinality::ZeroOrMany),
-            _ => (RelationshipType::OneToMany, Cardinality::One, Cardinality::ZeroOrMany),
+
+    // 属性1つ分を解釈する（`captures` は `line` に対する属性の正規表現の結果）
+    fn parse_attribute(
+        &mut self,
+        entity: &Entity,
+        captures: &Captures,
+        line: &str,
+        line_no: usize,
+        raw_line: &str,
+        annotations: Option<Annotations>,
+    ) -> Attribute {
+        let raw_type = captures.name("type").unwrap().as_str();
+        let params = captures.name("params").map_or("", |m| m.as_str());
+        // 修飾子・引数を含む型の記述全体（診断の位置に使う）
+        let type_end = ["modifier", "params", "post_modifier"]
+            .iter()
+            .filter_map(|group| captures.name(group))
+            .map(|m| m.end())
+            .max()
+            .unwrap_or(captures.name("type").unwrap().end());
+        let type_text = &line[captures.name("type").unwrap().start()..type_end];
+        let comment = captures.name("comment").map(|m| m.as_str().to_string());
+        let keys = parse_key_list(captures.name("keys").map(|m| m.as_str()));
+        let modifier = resolve_modifier(
+            captures.name("modifier").or_else(|| captures.name("post_modifier")).map(|m| m.as_str()),
+            comment.as_deref(),
+            keys.is_unique,
+        );
+        let attr_name = captures.name("name").unwrap().as_str().to_string();
+        let data_type = match LocoDataType::parse(&format!("{}{}{}", raw_type, modifier, params)) {
+            Ok(data_type) => data_type,
+            Err(DataTypeError::InvalidParameters { type_name, reason }) => {
+                self.problems.push(ParseError::InvalidTypeParameters {
+                    entity: entity.name.clone(),
+                    attribute: attr_name.clone(),
+                    error: DataTypeError::InvalidParameters { type_name, reason },
+                    span: Span::find(line_no, raw_line, type_text),
+                });
+                LocoDataType::from_mermaid_type(&format!("{}{}{}", raw_type, modifier, params))
+            }
+            Err(DataTypeError::UnsupportedConstraint(_)) => {
+                self.problems.push(ParseError::UnsupportedConstraint {
+                    entity: entity.name.clone(),
+                    attribute: attr_name.clone(),
+                    type_name: raw_type.to_string(),
+                    span: Span::find(line_no, raw_line, type_text),
+                });
+                LocoDataType::from_mermaid_type(&format!("{}!{}", raw_type, params))
+            }
+            Err(_) => {
+                self.problems.push(ParseError::UnknownType {
+                    entity: entity.name.clone(),
+                    attribute: attr_name.clone(),
+                    type_name: raw_type.to_string(),
+                    span: Span::find(line_no, raw_line, raw_type),
+                });
+                LocoDataType::from_mermaid_type(&format!("string{}", modifier))
+            }
+        };
+        // 修飾子もキーも無いカラムはnull許容
+        let is_nullable = captures.name("nullable").is_some()
+            || (modifier.is_empty() && !keys.is_primary_key && !keys.is_foreign_key);
+        
+        Attribute {
+            name: attr_name,
+            data_type: data_type.to_string(),
+            is_primary_key: keys.is_primary_key,
+            is_foreign_key: keys.is_foreign_key,
+            is_unique: data_type.constraint == Constraint::Unique,
+            is_nullable,
+            comment,
+            annotations: annotations.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct KeyList {
+    is_primary_key: bool,
+    is_foreign_key: bool,
+    is_unique: bool,
+}
+
+// `PK, FK` や `PK FK`、`UK` といったキーの並びを解釈する
+fn parse_key_list(keys: Option<&str>) -> KeyList {
+    let mut key_list = KeyList::default();
+    for key in keys
+        .unwrap_or("")
+        .split(|ch: char| ch.is_whitespace() || ch == ',')
+        .filter(|k| !k.is_empty())
+    {
+        match key {
+            "PK" => key_list.is_primary_key = true,
+            "FK" => key_list.is_foreign_key = true,
+            "UK" => key_list.is_unique = true,
+            _ => {}
         }
     }
+    key_list
+}
+
+// 型名の修飾子・`UK`・コメントのキーワードから、最終的な修飾子（`!` / `^` / なし）を決める
+// ユニーク制約は必須も兼ねるため `^` を優先する
+fn resolve_modifier(type_modifier: Option<&str>, comment: Option<&str>, is_unique_key: bool) -> &'static str {
+    let keywords: Vec<&str> = comment
+        .map(|c| c.split(|ch: char| ch.is_whitespace() || ch == ',').collect())
+        .unwrap_or_default();
+    let is_unique = type_modifier == Some("^") || is_unique_key || keywords.contains(&"Unique");
+    let is_required = type_modifier == Some("!") || keywords.contains(&"Require");
+
+    if is_unique {
+        "^"
+    } else if is_required {
+        "!"
+    } else {
+        ""
+    }
+}
+
+// カーディナリティの記号・別名を解釈する
+// 記号は向きによって左右反転した形（`|o` と `o|` など）を取る
+fn parse_cardinality(token: &str) -> Cardinality {
+    let normalized = token.split_whitespace().collect::<Vec<_>>().join(" ");
+    match normalized.as_str() {
+        "|o" | "o|" | "one or zero" | "zero or one" => Cardinality::ZeroOrOne,
+        "||" | "only one" | "1" => Cardinality::One,
+        "}o" | "o{" | "zero or more" | "zero or many" | "many(0)" | "0+" => Cardinality::ZeroOrMany,
+        "}|" | "|{" | "one or more" | "one or many" | "many(1)" | "1+" => Cardinality::OneOrMany,
+        _ => unreachable!("relationship_regex only captures known cardinalities: {}", token),
+    }
+}
+
+// `%% triton: scaffold=api, table=blog_posts` の `triton:` 以降を解釈する
+// 値を省略したキーは "true" とする
+fn parse_annotations(hints: &str) -> Annotations {
+    hints
+        .split(|ch: char| ch.is_whitespace() || ch == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.trim_matches('"').to_string()),
+            None => (token.to_string(), "true".to_string()),
+        })
+        .collect()
 }
@@ -0,0 +1,62 @@
+use crate::generator::LocoCommand;
+use crate::naming::to_table_name;
+
+// コマンドを、Locoプロジェクトのルートでそのまま実行できるbashスクリプトにする
+pub fn render_script(commands: &[LocoCommand], source: &str) -> String {
+    let mut lines = vec![
+        "#!/usr/bin/env bash".to_string(),
+        format!("# Generated by triton from {}", source),
+        "set -euo pipefail".to_string(),
+        String::new(),
+        "# Locoプロジェクトのルートで実行されているか確認".to_string(),
+        "if [ ! -f Cargo.toml ] || ! grep -q 'loco-rs' Cargo.toml; then".to_string(),
+        "  echo \"error: Locoプロジェクトのルートで実行してください\" >&2".to_string(),
+        "  exit 1".to_string(),
+        "fi".to_string(),
+    ];
+
+    let total = commands.len();
+    for (i, command) in commands.iter().enumerate() {
+        let quoted = quote_command(&command.command);
+        lines.push(String::new());
+        lines.push(format!("echo {}", shell_quote(&format!("==> [{}/{}] {}", i + 1, total, command.command))));
+
+        match &command.model {
+            // 既にモデルがあればscaffoldし直さない
+            Some(model) => {
+                // Locoは `table=` 注釈に関わらずモデル名からファイル名を決める
+                let model_file = format!("src/models/{}.rs", to_table_name(model));
+                lines.push(format!("if [ -f {} ]; then", model_file));
+                lines.push(format!(
+                    "  echo {}",
+                    shell_quote(&format!("    skip: {} already exists", model_file))
+                ));
+                lines.push("else".to_string());
+                lines.push(format!("  {}", quoted));
+                lines.push("fi".to_string());
+            }
+            None => lines.push(quoted),
+        }
+    }
+
+    lines.push(String::new());
+    lines.push("echo \"==> done\"".to_string());
+    lines.join("\n") + "\n"
+}
+
+fn quote_command(command: &str) -> String {
+    command.split_whitespace().map(shell_quote).collect::<Vec<_>>().join(" ")
+}
+
+// シェルで特別な意味を持つ文字を含む引数だけシングルクォートで囲む
+fn shell_quote(arg: &str) -> String {
+    let is_safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.,:/=+^!@%".contains(c));
+    if is_safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
@@ -0,0 +1,339 @@
+use crate::dependency::topological_order;
+use crate::diagnostic::Diagnostic;
+use crate::generator::ManagedColumn;
+use crate::migration::{foreign_keys, join_tables, ForeignKey};
+use crate::naming::{to_snake_case, to_table_name};
+use crate::types::*;
+use std::fmt;
+use std::str::FromStr;
+
+// DDLを出力するデータベース
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    #[default]
+    Postgres,
+    Sqlite,
+    Mysql,
+}
+
+impl Dialect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dialect::Postgres => "postgres",
+            Dialect::Sqlite => "sqlite",
+            Dialect::Mysql => "mysql",
+        }
+    }
+
+    fn quote(&self, identifier: &str) -> String {
+        match self {
+            Dialect::Mysql => format!("`{}`", identifier),
+            _ => format!("\"{}\"", identifier),
+        }
+    }
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "postgres" | "postgresql" | "pg" => Ok(Dialect::Postgres),
+            "sqlite" => Ok(Dialect::Sqlite),
+            "mysql" => Ok(Dialect::Mysql),
+            _ => Err(format!("'{}' は postgres・sqlite・mysql のいずれでもありません", s)),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Locoのマイグレーションが作るテーブルと同じ定義を `CREATE TABLE` で出力する
+pub struct SqlGenerator {
+    dialect: Dialect,
+    // 型に関係なく列定義から除くカラム（id / created_at / updated_at は常に出力する）
+    managed_columns: Vec<ManagedColumn>,
+}
+
+impl Default for SqlGenerator {
+    fn default() -> Self {
+        Self::new(Dialect::default())
+    }
+}
+
+// 参照カラムと、その外部キー制約
+struct Reference {
+    column: String,
+    column_type: String,
+    table: String,
+}
+
+impl SqlGenerator {
+    pub fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            managed_columns: ManagedColumn::loco_defaults(),
+        }
+    }
+
+    pub fn with_managed_columns(mut self, managed_columns: Vec<ManagedColumn>) -> Self {
+        self.managed_columns = managed_columns;
+        self
+    }
+
+    // 依存順の `CREATE TABLE` → 多対多の中間テーブル → 循環のため後回しにした外部キー
+    // SQLiteは `ADD CONSTRAINT` が無く、作成時に参照先を確認しないため後回しにしない
+    pub fn generate(&self, schema: &Schema) -> String {
+        let dependencies = topological_order(schema);
+        let mut statements = Vec::new();
+        let mut alters = Vec::new();
+
+        for name in &dependencies.order {
+            let entity = schema.find_entity(name).expect("order only contains defined entities");
+            let table = entity.table_name();
+            let mut constraints = Vec::new();
+            for foreign_key in foreign_keys(schema, entity) {
+                let reference = self.reference(&foreign_key);
+                let deferred = dependencies
+                    .deferred
+                    .iter()
+                    .any(|edge| edge.child == entity.name && edge.source == foreign_key.source);
+                if deferred && self.dialect != Dialect::Sqlite {
+                    alters.push(format!(
+                        "ALTER TABLE {} ADD {};",
+                        self.dialect.quote(&table),
+                        self.foreign_key_constraint(&table, &reference)
+                    ));
+                    constraints.push((reference, false));
+                } else {
+                    constraints.push((reference, true));
+                }
+            }
+            statements.push(self.create_table(entity, &constraints));
+        }
+
+        for join_table in join_tables(schema) {
+            let ends: Vec<Reference> = [&join_table.from, &join_table.to]
+                .iter()
+                .map(|name| {
+                    let target = schema.find_entity(name);
+                    Reference {
+                        column: format!("{}_id", to_snake_case(&schema.model_name_of(name))),
+                        column_type: self.reference_type(target),
+                        table: target.map_or_else(|| to_table_name(&schema.model_name_of(name)), Entity::table_name),
+                    }
+                })
+                .collect();
+            statements.push(self.create_join_table(&join_table.table, &ends));
+        }
+
+        statements.extend(alters);
+        statements.join("\n\n") + "\n"
+    }
+
+    fn create_table(&self, entity: &Entity, references: &[(Reference, bool)]) -> String {
+        let table = entity.table_name();
+        let mut lines = self.timestamp_columns();
+
+        let id = self.dialect.quote("id");
+        lines.push(match (entity.has_uuid_primary_key(), self.dialect) {
+            (true, _) => format!("{} {} NOT NULL PRIMARY KEY", id, self.uuid_type()),
+            (false, Dialect::Postgres) => format!("{} SERIAL PRIMARY KEY", id),
+            (false, Dialect::Sqlite) => format!("{} INTEGER PRIMARY KEY AUTOINCREMENT", id),
+            (false, Dialect::Mysql) => format!("{} INT NOT NULL AUTO_INCREMENT PRIMARY KEY", id),
+        });
+
+        for attr in &entity.attributes {
+            let is_managed = self.managed_columns.iter().any(|c| c.name == attr.name);
+            if attr.is_primary_key || attr.is_foreign_key || is_managed {
+                continue;
+            }
+            let data_type = LocoDataType::from_mermaid_type(&attr.data_type);
+            let constraint = match data_type.constraint {
+                Constraint::Nullable => "",
+                Constraint::Unique if !self.can_be_unique(&data_type) => " NOT NULL",
+                Constraint::Required => " NOT NULL",
+                Constraint::Unique => " NOT NULL UNIQUE",
+            };
+            lines.push(format!(
+                "{} {}{}",
+                self.dialect.quote(&attr.name),
+                self.column_type(&data_type),
+                constraint
+            ));
+        }
+
+        for (reference, _) in references {
+            lines.push(self.reference_column(reference));
+        }
+        for (reference, inline) in references {
+            if *inline {
+                lines.push(self.foreign_key_constraint(&table, reference));
+            }
+        }
+
+        self.render_create_table(&table, &lines)
+    }
+
+    // MySQLは長さの決まらない TEXT・BLOB・JSON のカラムにユニーク制約を付けられない
+    fn can_be_unique(&self, data_type: &LocoDataType) -> bool {
+        self.dialect != Dialect::Mysql || !["TEXT", "BLOB", "JSON"].contains(&self.column_type(data_type).as_str())
+    }
+
+    // DDLに反映できない制約
+    pub fn diagnostics(&self, schema: &Schema) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for entity in &schema.entities {
+            for attr in &entity.attributes {
+                let is_managed = self.managed_columns.iter().any(|c| c.name == attr.name);
+                if attr.is_primary_key || attr.is_foreign_key || is_managed {
+                    continue;
+                }
+                let data_type = LocoDataType::from_mermaid_type(&attr.data_type);
+                if data_type.constraint == Constraint::Unique && !self.can_be_unique(&data_type) {
+                    diagnostics.push(
+                        Diagnostic::warning(format!(
+                            "{}.{} の型 {} はMySQLでユニーク制約を付けられないため、UNIQUE を出力しません",
+                            entity.name,
+                            attr.name,
+                            self.column_type(&data_type)
+                        ))
+                        .with_help("`string^` のような長さの決まる型にするか、プレフィックス長付きのユニークインデックスを手動で追加してください"),
+                    );
+                }
+            }
+        }
+        diagnostics
+    }
+
+    fn create_join_table(&self, table: &str, ends: &[Reference]) -> String {
+        let mut lines = self.timestamp_columns();
+        lines.extend(ends.iter().map(|end| self.reference_column(end)));
+        let columns: Vec<String> = ends.iter().map(|end| self.dialect.quote(&end.column)).collect();
+        lines.push(format!("PRIMARY KEY ({})", columns.join(", ")));
+        lines.extend(ends.iter().map(|end| self.foreign_key_constraint(table, end)));
+        self.render_create_table(table, &lines)
+    }
+
+    fn render_create_table(&self, table: &str, lines: &[String]) -> String {
+        let body: Vec<String> = lines.iter().map(|line| format!("    {}", line)).collect();
+        format!("CREATE TABLE {} (\n{}\n);", self.dialect.quote(table), body.join(",\n"))
+    }
+
+    // Locoの `timestamps_tz` と同じ作成・更新日時
+    fn timestamp_columns(&self) -> Vec<String> {
+        let column_type = self.column_type(&LocoDataType {
+            base: LocoBaseType::Tstz,
+            constraint: Constraint::Required,
+            params: None,
+        });
+        ["created_at", "updated_at"]
+            .iter()
+            .map(|name| format!("{} {} NOT NULL DEFAULT CURRENT_TIMESTAMP", self.dialect.quote(name), column_type))
+            .collect()
+    }
+
+    fn reference(&self, foreign_key: &ForeignKey) -> Reference {
+        let base = foreign_key.column.strip_suffix("_id").unwrap_or(&foreign_key.column);
+        Reference {
+            column: foreign_key.column.clone(),
+            column_type: self.reference_type(foreign_key.target),
+            table: foreign_key.target.map_or_else(|| to_table_name(base), Entity::table_name),
+        }
+    }
+
+    // 参照先の主キーと同じ型
+    fn reference_type(&self, target: Option<&Entity>) -> String {
+        match (target.is_some_and(Entity::has_uuid_primary_key), self.dialect) {
+            (true, _) => self.uuid_type().to_string(),
+            (false, Dialect::Mysql) => "INT".to_string(),
+            (false, _) => "INTEGER".to_string(),
+        }
+    }
+
+    fn reference_column(&self, reference: &Reference) -> String {
+        format!("{} {} NOT NULL", self.dialect.quote(&reference.column), reference.column_type)
+    }
+
+    // Locoの `create_table` と同じく、参照先が削除されたら行ごと削除する
+    fn foreign_key_constraint(&self, table: &str, reference: &Reference) -> String {
+        format!(
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE CASCADE ON UPDATE CASCADE",
+            self.dialect.quote(&format!("fk-{}-{}-to-{}", table, reference.column, reference.table)),
+            self.dialect.quote(&reference.column),
+            self.dialect.quote(&reference.table),
+            self.dialect.quote("id")
+        )
+    }
+
+    fn uuid_type(&self) -> &'static str {
+        match self.dialect {
+            Dialect::Postgres => "UUID",
+            Dialect::Sqlite => "TEXT",
+            Dialect::Mysql => "BINARY(16)",
+        }
+    }
+
+    // Locoの `ColType` がSeaQueryを通して各データベースで作る型
+    pub fn column_type(&self, data_type: &LocoDataType) -> String {
+        let dialect = self.dialect;
+        let data_type = data_type.fill_missing_params();
+        let pick = |postgres: &str, sqlite: &str, mysql: &str| {
+            match dialect {
+                Dialect::Postgres => postgres,
+                Dialect::Sqlite => sqlite,
+                Dialect::Mysql => mysql,
+            }
+            .to_string()
+        };
+        match data_type.base {
+            LocoBaseType::Uuid => self.uuid_type().to_string(),
+            LocoBaseType::String => pick("VARCHAR", "VARCHAR", "VARCHAR(255)"),
+            LocoBaseType::Text => "TEXT".to_string(),
+            LocoBaseType::SmallInt => "SMALLINT".to_string(),
+            LocoBaseType::Int => pick("INTEGER", "INTEGER", "INT"),
+            LocoBaseType::BigInt => "BIGINT".to_string(),
+            LocoBaseType::SmallUnsigned => pick("SMALLINT", "SMALLINT", "SMALLINT UNSIGNED"),
+            LocoBaseType::Unsigned => pick("INTEGER", "INTEGER", "INT UNSIGNED"),
+            LocoBaseType::BigUnsigned => pick("BIGINT", "BIGINT", "BIGINT UNSIGNED"),
+            LocoBaseType::Float => pick("REAL", "REAL", "FLOAT"),
+            LocoBaseType::Double => pick("DOUBLE PRECISION", "REAL", "DOUBLE"),
+            LocoBaseType::Decimal => "DECIMAL".to_string(),
+            LocoBaseType::DecimalLen => match data_type.params {
+                Some(TypeParams::Precision { precision, scale }) => format!("DECIMAL({}, {})", precision, scale),
+                _ => "DECIMAL".to_string(),
+            },
+            LocoBaseType::Money => pick("MONEY", "DECIMAL(19, 4)", "DECIMAL(19, 4)"),
+            LocoBaseType::Bool => pick("BOOLEAN", "BOOLEAN", "BOOL"),
+            LocoBaseType::Tstz => pick("TIMESTAMP WITH TIME ZONE", "TIMESTAMP", "TIMESTAMP"),
+            LocoBaseType::Date => "DATE".to_string(),
+            LocoBaseType::DateTime => pick("TIMESTAMP WITHOUT TIME ZONE", "DATETIME", "DATETIME"),
+            LocoBaseType::Blob => pick("BYTEA", "BLOB", "BLOB"),
+            LocoBaseType::BinaryLen => match (data_type.params, dialect) {
+                (Some(TypeParams::Length(length)), Dialect::Mysql) => format!("BINARY({})", length),
+                _ => pick("BYTEA", "BLOB", "BLOB"),
+            },
+            LocoBaseType::VarBinary => pick("BYTEA", "BLOB", "VARBINARY(255)"),
+            LocoBaseType::Json => pick("JSON", "TEXT", "JSON"),
+            LocoBaseType::JsonBinary => pick("JSONB", "TEXT", "JSON"),
+            // 配列はPostgreSQLのみ。他のデータベースではJSONとして保存する
+            LocoBaseType::Array => match dialect {
+                Dialect::Postgres => {
+                    let element = match data_type.params {
+                        Some(TypeParams::Element(LocoBaseType::Int)) => "INTEGER",
+                        Some(TypeParams::Element(LocoBaseType::Float)) => "REAL",
+                        Some(TypeParams::Element(LocoBaseType::Bool)) => "BOOLEAN",
+                        _ => "VARCHAR",
+                    };
+                    format!("{}[]", element)
+                }
+                Dialect::Sqlite => "TEXT".to_string(),
+                Dialect::Mysql => "JSON".to_string(),
+            },
+        }
+    }
+}
@@ -1,43 +1,214 @@
-use std::collections::HashMap;
+use crate::error::{DataTypeError, Span};
+use crate::naming::{is_identifier, to_pascal_case, to_snake_case, to_table_name};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone)]
+// `%% triton:` 注釈のキーと値
+pub type Annotations = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Schema {
     pub entities: Vec<Entity>,
+    #[serde(default)]
     pub relationships: Vec<Relationship>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Entity {
+    // リレーションシップから参照される識別子（`"Order Item"` なら引用符の中身）
     pub name: String,
+    // `p[Person]` の `Person` のような表示名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default)]
     pub attributes: Vec<Attribute>,
+    // `%% triton: key=value` で付けられた生成時のヒント
+    #[serde(default, skip_serializing_if = "Annotations::is_empty")]
+    pub annotations: Annotations,
+}
+
+impl Entity {
+    pub fn annotation(&self, key: &str) -> Option<&str> {
+        self.annotations.get(key).map(String::as_str)
+    }
+
+    // テーブル名（`%% triton: table=...` の注釈が無ければモデル名の複数形）
+    pub fn table_name(&self) -> String {
+        match self.annotation("table") {
+            Some(table) => table.to_string(),
+            None => to_table_name(&self.model_name()),
+        }
+    }
+
+    // Locoのモデル名として使う名前
+    // 識別子として正しい方を優先し（表示名 → 識別子の順）、どちらも使えなければPascalCaseに変換する
+    // 変換しても識別子にならない場合（`"ユーザー"` のような英数字の無い名前など）は `Entity` を前に付ける
+    pub fn model_name(&self) -> String {
+        if let Some(alias) = self.alias.as_deref().filter(|a| is_identifier(a)) {
+            return alias.to_string();
+        }
+        if is_identifier(&self.name) {
+            return self.name.clone();
+        }
+        let converted = to_pascal_case(self.alias.as_deref().unwrap_or(&self.name));
+        if is_identifier(&converted) {
+            converted
+        } else {
+            format!("Entity{}", converted)
+        }
+    }
+
+    // 名前・表示名からモデル名を作れるか（作れなければ `model_name` は代わりの名前を返す）
+    pub fn has_model_name(&self) -> bool {
+        let converted = to_pascal_case(self.alias.as_deref().unwrap_or(&self.name));
+        self.alias.as_deref().is_some_and(is_identifier) || is_identifier(&self.name) || is_identifier(&converted)
+    }
+
+    // 主キーがUUIDか（参照するカラムも同じ型にする）
+    pub fn has_uuid_primary_key(&self) -> bool {
+        self.attributes
+            .iter()
+            .find(|a| a.is_primary_key)
+            .is_some_and(|pk| LocoDataType::from_mermaid_type(&pk.data_type).base == LocoBaseType::Uuid)
+    }
+}
+
+impl Schema {
+    pub fn find_entity(&self, name: &str) -> Option<&Entity> {
+        self.entities.iter().find(|e| e.name == name)
+    }
+
+    // FK属性が参照するエンティティ
+    // 1. `%% triton: references=...` の注釈
+    // 2. `user_id` → `User` のような名前の一致
+    // 3. リレーションシップ上の親が1つだけならその親
+    pub fn foreign_key_target(&self, entity: &Entity, attr: &Attribute) -> Option<&Entity> {
+        if let Some(target) = attr.annotation("references") {
+            return self.entities.iter().find(|e| e.name == target || e.model_name() == target);
+        }
+
+        let base = attr.name.strip_suffix("_id").unwrap_or(&attr.name);
+        let by_name = self
+            .entities
+            .iter()
+            .find(|e| to_snake_case(&e.model_name()) == base || to_snake_case(&e.name) == base);
+        if by_name.is_some() {
+            return by_name;
+        }
+
+        let mut parents: Vec<&str> = self
+            .relationships
+            .iter()
+            .filter_map(|r| r.parent_and_child())
+            .filter(|(_, child)| *child == entity.name)
+            .map(|(parent, _)| parent)
+            .collect();
+        parents.dedup();
+        match parents.as_slice() {
+            [parent] => self.find_entity(parent),
+            _ => None,
+        }
+    }
+
+    // リレーションシップに書かれた名前からモデル名を引く（未定義ならそのまま使う）
+    pub fn model_name_of(&self, name: &str) -> String {
+        self.find_entity(name)
+            .map(|e| e.model_name())
+            .unwrap_or_else(|| to_pascal_case(name))
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Attribute {
     pub name: String,
     pub data_type: String,
+    #[serde(default)]
     pub is_primary_key: bool,
+    #[serde(default)]
     pub is_foreign_key: bool,
+    // `UK` / `^` / "Unique" のいずれかでユニーク制約を持つ
+    #[serde(default)]
+    pub is_unique: bool,
+    #[serde(default)]
     pub is_nullable: bool,
+    // `型名 カラム名 [PK,FK]` の後ろに書かれたコメント
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Annotations::is_empty")]
+    pub annotations: Annotations,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Relationship {
     pub from_entity: String,
     pub to_entity: String,
     pub relationship_type: RelationshipType,
     pub from_cardinality: Cardinality,
     pub to_cardinality: Cardinality,
+    // `--` なら識別関係、`..` なら非識別関係
+    #[serde(default)]
+    pub is_identifying: bool,
+    // 図中で記述された位置（診断表示用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
 }
 
-#[derive(Debug, Clone)]
+impl Attribute {
+    pub fn annotation(&self, key: &str) -> Option<&str> {
+        self.annotations.get(key).map(String::as_str)
+    }
+}
+
+impl Relationship {
+    // 参照される親側と、`references` カラムを持つ子側を (親, 子) の順で返す
+    // 記述の向きに関係なく「多」側を子とし、1対1では省略可能な側を子とする
+    // 多対多は中間テーブルが必要なため None を返す
+    pub fn parent_and_child(&self) -> Option<(&str, &str)> {
+        let from = self.from_entity.as_str();
+        let to = self.to_entity.as_str();
+        match self.relationship_type {
+            RelationshipType::ManyToMany => None,
+            RelationshipType::OneToMany => {
+                if self.from_cardinality.is_many() {
+                    Some((to, from))
+                } else {
+                    Some((from, to))
+                }
+            }
+            RelationshipType::OneToOne => {
+                if self.from_cardinality == Cardinality::ZeroOrOne && self.to_cardinality != Cardinality::ZeroOrOne {
+                    Some((to, from))
+                } else {
+                    Some((from, to))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RelationshipType {
     OneToOne,
     OneToMany,
     ManyToMany,
 }
 
-#[derive(Debug, Clone)]
+impl RelationshipType {
+    // 両端のカーディナリティから関係の種類を決める
+    pub fn from_cardinalities(from: &Cardinality, to: &Cardinality) -> Self {
+        match (from.is_many(), to.is_many()) {
+            (true, true) => RelationshipType::ManyToMany,
+            (false, false) => RelationshipType::OneToOne,
+            _ => RelationshipType::OneToMany,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Cardinality {
     Zero,
     One,
@@ -46,339 +217,440 @@ pub enum Cardinality {
     OneOrMany,
 }
 
-#[derive(Debug)]
-pub enum LocoDataType {
-    // UUID
-    UuidNullable,
-    UuidUnique,
-    Uuid,
+impl Cardinality {
+    pub fn is_many(&self) -> bool {
+        matches!(self, Cardinality::ZeroOrMany | Cardinality::OneOrMany)
+    }
+}
 
-    // String
-    StringNullable,
-    StringUnique,
+// Locoのscaffoldが受け付ける型（`!` / `^` を除いた部分）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocoBaseType {
+    Uuid,
     String,
-
-    // Text
-    TextNullable,
-    TextUnique,
     Text,
-
-    // Small Unsigned Int
-    SmallUnsignedNullable,
-    SmallUnsignedUnique,
     SmallUnsigned,
-
-    // Big Unsigned Int
-    BigUnsignedNullable,
-    BigUnsignedUnique,
     BigUnsigned,
-
-    // Small Int
-    SmallIntNullable,
-    SmallIntUnique,
     SmallInt,
-
-    // Int
-    IntNullable,
-    IntUnique,
     Int,
-
-    // Big Int
-    BigIntNullable,
-    BigIntUnique,
     BigInt,
-
-    // Float
-    FloatNullable,
-    FloatUnique,
     Float,
-
-    // Double
-    DoubleNullable,
-    DoubleUnique,
     Double,
-
-    // Decimal
-    DecimalNullable,
-    DecimalUnique,
     Decimal,
-
-    // Decimal with length
-    DecimalLenNullable,
     DecimalLen,
-
-    // Boolean
-    BooleanNullable,
-    Boolean,
-
-    // Timestamp with timezone
-    TimestampWithTimeZoneNullable,
-    TimestampWithTimeZone,
-
-    // Date
-    DateNullable,
-    DateUnique,
+    Bool,
+    Tstz,
     Date,
-
-    // DateTime
-    DateTimeNullable,
-    DateTimeUnique,
     DateTime,
-
-    // Blob
-    BlobNullable,
-    BlobUnique,
     Blob,
-
-    // JSON
-    JsonNullable,
     Json,
-
-    // JSON Binary
-    JsonBinaryNullable,
-    JsonBinaryUnique,
     JsonBinary,
-
-    // Money
-    MoneyNullable,
-    MoneyUnique,
     Money,
-
-    // Unsigned
-    UnsignedNullable,
-    UnsignedUnique,
     Unsigned,
-
-    // Binary Length
-    BinaryLenNullable,
-    BinaryLenUnique,
     BinaryLen,
-
-    // VarBinary
-    VarBinaryNullable,
     VarBinary,
-
-    // Array（変数名の先頭空白を修正）
     Array,
-    ArrayUnique,
-    ArrayNullable,
 }
 
-impl LocoDataType {
-    pub fn from_mermaid_type(mermaid_type: &str) -> Self {
-        match mermaid_type.to_lowercase().as_str() {
-            // UUID
-            "uuid" => LocoDataType::UuidNullable,
-            "uuid!" => LocoDataType::Uuid,
-            "uuid^" => LocoDataType::UuidUnique,
-
-            // String
-            "string" => LocoDataType::StringNullable,
-            "string!" => LocoDataType::String,
-            "string^" => LocoDataType::StringUnique,
-
-            // Text
-            "text" => LocoDataType::TextNullable,
-            "text!" => LocoDataType::Text,
-            "text^" => LocoDataType::TextUnique,
-
-            // Small Unsigned
-            "small_unsigned" => LocoDataType::SmallUnsignedNullable,
-            "small_unsigned!" => LocoDataType::SmallUnsigned,
-            "small_unsigned^" => LocoDataType::SmallUnsignedUnique,
-
-            // Big Unsigned
-            "big_unsigned" => LocoDataType::BigUnsigned,
-            "big_unsigned!" => LocoDataType::BigUnsignedUnique,
-            "big_unsigned^" => LocoDataType::BigUnsignedNullable,
-
-            // Small Int
-            "small_int" => LocoDataType::SmallIntNullable,
-            "small_int!" => LocoDataType::SmallInt,
-            "small_int^" => LocoDataType::SmallIntUnique,
-
-            // Int
-            "int" => LocoDataType::IntNullable,
-            "int!" => LocoDataType::Int,
-            "int^" => LocoDataType::IntUnique,
-
-            // Big Int
-            "big_int" => LocoDataType::BigIntNullable,
-            "big_int!" => LocoDataType::BigInt,
-            "big_int^" => LocoDataType::BigIntUnique,
-
-            // Float
-            "float" => LocoDataType::FloatNullable,
-            "float!" => LocoDataType::Float,
-            "float^" => LocoDataType::FloatUnique,
-
-            // Double
-            "double" => LocoDataType::DoubleNullable,
-            "double!" => LocoDataType::Double,
-            "double^" => LocoDataType::DoubleUnique,
-
-            // Decimal
-            "decimal" => LocoDataType::DecimalNullable,
-            "decimal!" => LocoDataType::Decimal,
-            "decimal^" => LocoDataType::DecimalUnique,
-
-            // Decimal with length
-            "decimal_len" => LocoDataType::DecimalLenNullable,
-            "decimal_len!" => LocoDataType::DecimalLen,
-
-            // Boolean
-            "bool" => LocoDataType::BooleanNullable,
-            "bool!" => LocoDataType::Boolean,
-
-            // Timestamp with time zone
-            "tstz" => LocoDataType::TimestampWithTimeZoneNullable,
-            "tstz!" => LocoDataType::TimestampWithTimeZone,
-
-            // Date
-            "date" => LocoDataType::DateNullable,
-            "date!" => LocoDataType::Date,
-            "date^" => LocoDataType::DateUnique,
-
-            // DateTime
-            "date_time" => LocoDataType::DateTimeNullable,
-            "date_time!" => LocoDataType::DateTime,
-            "date_time^" => LocoDataType::DateTimeUnique,
-
-            // Blob
-            "blob" => LocoDataType::BlobNullable,
-            "blob!" => LocoDataType::Blob,
-            "blob^" => LocoDataType::BlobUnique,
-
-            // JSON
-            "json" => LocoDataType::JsonNullable,
-            "json!" => LocoDataType::Json,
-
-            // JSON Binary
-            "jsonb" => LocoDataType::JsonBinaryNullable,
-            "jsonb!" => LocoDataType::JsonBinary,
-            "jsonb^" => LocoDataType::JsonBinaryUnique,
-
-            // Money
-            "money" => LocoDataType::MoneyNullable,
-            "money!" => LocoDataType::Money,
-            "money^" => LocoDataType::MoneyUnique,
-
-            // Unsigned
-            "unsigned" => LocoDataType::UnsignedNullable,
-            "unsigned!" => LocoDataType::Unsigned,
-            "unsigned^" => LocoDataType::UnsignedUnique,
-
-            // Binary Length
-            "binary_len" => LocoDataType::BinaryLenNullable,
-            "binary_len!" => LocoDataType::BinaryLen,
-            "binary_len^" => LocoDataType::BinaryLenUnique,
-
-            // Var Binary
-            "var_binary" => LocoDataType::VarBinaryNullable,
-            "var_binary!" => LocoDataType::VarBinary,
-
-            // Array
-            "array" => LocoDataType::Array,
-            "array!" => LocoDataType::ArrayUnique,
-            "array^" => LocoDataType::ArrayNullable,
-
-            // fallback
-            _ => LocoDataType::StringNullable,
+impl LocoBaseType {
+    pub const ALL: [LocoBaseType; 24] = [
+        LocoBaseType::Uuid,
+        LocoBaseType::String,
+        LocoBaseType::Text,
+        LocoBaseType::SmallUnsigned,
+        LocoBaseType::BigUnsigned,
+        LocoBaseType::SmallInt,
+        LocoBaseType::Int,
+        LocoBaseType::BigInt,
+        LocoBaseType::Float,
+        LocoBaseType::Double,
+        LocoBaseType::Decimal,
+        LocoBaseType::DecimalLen,
+        LocoBaseType::Bool,
+        LocoBaseType::Tstz,
+        LocoBaseType::Date,
+        LocoBaseType::DateTime,
+        LocoBaseType::Blob,
+        LocoBaseType::Json,
+        LocoBaseType::JsonBinary,
+        LocoBaseType::Money,
+        LocoBaseType::Unsigned,
+        LocoBaseType::BinaryLen,
+        LocoBaseType::VarBinary,
+        LocoBaseType::Array,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LocoBaseType::Uuid => "uuid",
+            LocoBaseType::String => "string",
+            LocoBaseType::Text => "text",
+            LocoBaseType::SmallUnsigned => "small_unsigned",
+            LocoBaseType::BigUnsigned => "big_unsigned",
+            LocoBaseType::SmallInt => "small_int",
+            LocoBaseType::Int => "int",
+            LocoBaseType::BigInt => "big_int",
+            LocoBaseType::Float => "float",
+            LocoBaseType::Double => "double",
+            LocoBaseType::Decimal => "decimal",
+            LocoBaseType::DecimalLen => "decimal_len",
+            LocoBaseType::Bool => "bool",
+            LocoBaseType::Tstz => "tstz",
+            LocoBaseType::Date => "date",
+            LocoBaseType::DateTime => "date_time",
+            LocoBaseType::Blob => "blob",
+            LocoBaseType::Json => "json",
+            LocoBaseType::JsonBinary => "jsonb",
+            LocoBaseType::Money => "money",
+            LocoBaseType::Unsigned => "unsigned",
+            LocoBaseType::BinaryLen => "binary_len",
+            LocoBaseType::VarBinary => "var_binary",
+            LocoBaseType::Array => "array",
         }
     }
 
-    pub fn to_loco_type(&self) -> &'static str {
-        match self {
-            LocoDataType::UuidNullable => "uuid",
-            LocoDataType::Uuid => "uuid!",
-            LocoDataType::UuidUnique => "uuid^",
+    // Locoのscaffoldで引数が必須の型
+    pub fn requires_params(&self) -> bool {
+        matches!(self, LocoBaseType::DecimalLen | LocoBaseType::BinaryLen | LocoBaseType::Array)
+    }
 
-            LocoDataType::StringNullable => "string",
-            LocoDataType::String => "string!",
-            LocoDataType::StringUnique => "string^",
+    // Locoに `^`（unique）の指定が無い型
+    pub fn supports_unique(&self) -> bool {
+        !matches!(
+            self,
+            LocoBaseType::DecimalLen
+                | LocoBaseType::Bool
+                | LocoBaseType::Tstz
+                | LocoBaseType::Json
+                | LocoBaseType::VarBinary
+        )
+    }
+}
 
-            LocoDataType::TextNullable => "text",
-            LocoDataType::Text => "text!",
-            LocoDataType::TextUnique => "text^",
+impl FromStr for LocoBaseType {
+    type Err = DataTypeError;
 
-            LocoDataType::SmallUnsignedNullable => "small_unsigned",
-            LocoDataType::SmallUnsigned => "small_unsigned!",
-            LocoDataType::SmallUnsignedUnique => "small_unsigned^",
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        LocoBaseType::ALL
+            .into_iter()
+            .find(|base| base.name() == lower)
+            .ok_or_else(|| DataTypeError::UnknownType(s.to_string()))
+    }
+}
 
-            LocoDataType::BigUnsigned => "big_unsigned",
-            LocoDataType::BigUnsignedUnique => "big_unsigned!",
-            LocoDataType::BigUnsignedNullable => "big_unsigned^",
+impl fmt::Display for LocoBaseType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
 
-            LocoDataType::SmallIntNullable => "small_int",
-            LocoDataType::SmallInt => "small_int!",
-            LocoDataType::SmallIntUnique => "small_int^",
+// 型名の後ろの修飾子: 無し = null許容、`!` = 必須、`^` = unique
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    Nullable,
+    Required,
+    Unique,
+}
 
-            LocoDataType::IntNullable => "int",
-            LocoDataType::Int => "int!",
-            LocoDataType::IntUnique => "int^",
+impl Constraint {
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Constraint::Nullable => "",
+            Constraint::Required => "!",
+            Constraint::Unique => "^",
+        }
+    }
+}
 
-            LocoDataType::BigIntNullable => "big_int",
-            LocoDataType::BigInt => "big_int!",
-            LocoDataType::BigIntUnique => "big_int^",
+impl FromStr for Constraint {
+    type Err = DataTypeError;
 
-            LocoDataType::FloatNullable => "float",
-            LocoDataType::Float => "float!",
-            LocoDataType::FloatUnique => "float^",
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(Constraint::Nullable),
+            "!" => Ok(Constraint::Required),
+            "^" => Ok(Constraint::Unique),
+            _ => Err(DataTypeError::UnknownType(s.to_string())),
+        }
+    }
+}
 
-            LocoDataType::DoubleNullable => "double",
-            LocoDataType::Double => "double!",
-            LocoDataType::DoubleUnique => "double^",
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
 
-            LocoDataType::DecimalNullable => "decimal",
-            LocoDataType::Decimal => "decimal!",
-            LocoDataType::DecimalUnique => "decimal^",
+// `decimal_len:10:2` / `binary_len:16` / `array:string` のような型の引数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeParams {
+    // decimal_len: 全体の桁数と小数点以下の桁数
+    Precision { precision: u32, scale: u32 },
+    // binary_len: バイト数
+    Length(u32),
+    // array: 要素の型
+    Element(LocoBaseType),
+}
 
-            LocoDataType::DecimalLenNullable => "decimal_len",
-            LocoDataType::DecimalLen => "decimal_len!",
+// 桁数・バイト数の上限（MySQLの DECIMAL(65,30) / BINARY(255) に合わせる）
+pub const MAX_DECIMAL_PRECISION: u32 = 65;
+pub const MAX_DECIMAL_SCALE: u32 = 30;
+pub const MAX_BINARY_LENGTH: u32 = 255;
+
+// Locoの `array` が要素として受け付ける型
+const ARRAY_ELEMENT_TYPES: &[LocoBaseType] = &[
+    LocoBaseType::String,
+    LocoBaseType::Int,
+    LocoBaseType::Float,
+    LocoBaseType::Bool,
+];
+
+impl TypeParams {
+    // 型ごとに引数の数と範囲を確認する
+    pub fn parse(base: LocoBaseType, args: &[&str]) -> Result<Self, DataTypeError> {
+        let invalid = |reason: String| DataTypeError::InvalidParameters {
+            type_name: base.name().to_string(),
+            reason,
+        };
+        let number = |arg: &str| -> Result<u32, DataTypeError> {
+            arg.parse().map_err(|_| invalid(format!("'{}' は数値ではありません", arg)))
+        };
+
+        match (base, args) {
+            (LocoBaseType::DecimalLen, [precision, scale]) => {
+                let (precision, scale) = (number(precision)?, number(scale)?);
+                if !(1..=MAX_DECIMAL_PRECISION).contains(&precision) {
+                    return Err(invalid(format!(
+                        "桁数は1〜{}で指定してください（{}）",
+                        MAX_DECIMAL_PRECISION, precision
+                    )));
+                }
+                if scale > precision.min(MAX_DECIMAL_SCALE) {
+                    return Err(invalid(format!(
+                        "小数点以下の桁数は0〜{}で指定してください（{}）",
+                        precision.min(MAX_DECIMAL_SCALE),
+                        scale
+                    )));
+                }
+                Ok(TypeParams::Precision { precision, scale })
+            }
+            (LocoBaseType::BinaryLen, [length]) => {
+                let length = number(length)?;
+                if !(1..=MAX_BINARY_LENGTH).contains(&length) {
+                    return Err(invalid(format!(
+                        "バイト数は1〜{}で指定してください（{}）",
+                        MAX_BINARY_LENGTH, length
+                    )));
+                }
+                Ok(TypeParams::Length(length))
+            }
+            (LocoBaseType::Array, [element]) => {
+                let element = resolve_alias(element).parse::<LocoBaseType>()?;
+                if !ARRAY_ELEMENT_TYPES.contains(&element) {
+                    let names: Vec<&str> = ARRAY_ELEMENT_TYPES.iter().map(|t| t.name()).collect();
+                    return Err(invalid(format!(
+                        "要素の型は {} のいずれかを指定してください（{}）",
+                        names.join(", "),
+                        element
+                    )));
+                }
+                Ok(TypeParams::Element(element))
+            }
+            (LocoBaseType::DecimalLen, _) => Err(invalid("`decimal_len(桁数,小数点以下の桁数)` の形式で指定してください".to_string())),
+            (LocoBaseType::BinaryLen, _) => Err(invalid("`binary_len(バイト数)` の形式で指定してください".to_string())),
+            (LocoBaseType::Array, _) => Err(invalid("`array<要素の型>` の形式で指定してください".to_string())),
+            _ => Err(invalid("この型は引数を取りません".to_string())),
+        }
+    }
+}
 
-            LocoDataType::BooleanNullable => "bool",
-            LocoDataType::Boolean => "bool!",
+impl fmt::Display for TypeParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeParams::Precision { precision, scale } => write!(f, ":{}:{}", precision, scale),
+            TypeParams::Length(length) => write!(f, ":{}", length),
+            TypeParams::Element(element) => write!(f, ":{}", element),
+        }
+    }
+}
 
-            LocoDataType::TimestampWithTimeZoneNullable => "tstz",
-            LocoDataType::TimestampWithTimeZone => "tstz!",
+// `string!` / `decimal_len:10:2` のような、Locoのscaffoldに渡す型指定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocoDataType {
+    pub base: LocoBaseType,
+    pub constraint: Constraint,
+    pub params: Option<TypeParams>,
+}
 
-            LocoDataType::DateNullable => "date",
-            LocoDataType::Date => "date!",
-            LocoDataType::DateUnique => "date^",
+// SQL・Mermaidでよく使われる型名と、対応するLocoの型名
+const TYPE_ALIASES: &[(&str, &str)] = &[
+    // String / Text
+    ("varchar", "string"),
+    ("char", "string"),
+    ("character", "string"),
+    ("nvarchar", "string"),
+    ("str", "string"),
+    ("clob", "text"),
+    ("longtext", "text"),
+    ("mediumtext", "text"),
+    // Integer
+    ("integer", "int"),
+    ("int4", "int"),
+    ("int32", "int"),
+    ("serial", "int"),
+    ("smallint", "small_int"),
+    ("int2", "small_int"),
+    ("int16", "small_int"),
+    ("bigint", "big_int"),
+    ("int8", "big_int"),
+    ("int64", "big_int"),
+    ("long", "big_int"),
+    ("bigserial", "big_int"),
+    // Floating point / Decimal
+    ("real", "float"),
+    ("float4", "float"),
+    ("float8", "double"),
+    ("numeric", "decimal"),
+    // Boolean
+    ("boolean", "bool"),
+    // Date / Time
+    ("timestamp", "date_time"),
+    ("datetime", "date_time"),
+    ("timestamptz", "tstz"),
+    ("timestamp_tz", "tstz"),
+    // Binary
+    ("bytea", "blob"),
+    ("binary", "blob"),
+    ("varbinary", "var_binary"),
+];
+
+fn resolve_alias(name: &str) -> &str {
+    TYPE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, loco)| *loco)
+}
 
-            LocoDataType::DateTimeNullable => "date_time",
-            LocoDataType::DateTime => "date_time!",
-            LocoDataType::DateTimeUnique => "date_time^",
+impl LocoDataType {
+    pub fn new(base: LocoBaseType, constraint: Constraint) -> Result<Self, DataTypeError> {
+        if constraint == Constraint::Unique && !base.supports_unique() {
+            return Err(DataTypeError::UnsupportedConstraint(format!("{}{}", base, constraint)));
+        }
+        Ok(Self { base, constraint, params: None })
+    }
 
-            LocoDataType::BlobNullable => "blob",
-            LocoDataType::Blob => "blob!",
-            LocoDataType::BlobUnique => "blob^",
+    pub fn with_params(mut self, args: &[&str]) -> Result<Self, DataTypeError> {
+        self.params = Some(TypeParams::parse(self.base, args)?);
+        Ok(self)
+    }
 
-            LocoDataType::JsonNullable => "json",
-            LocoDataType::Json => "json!",
+    // 別名を解決してLocoの型に変換する
+    // 型名の大文字・小文字は区別せず、引数は `decimal_len(10,2)` / `array<string>` と
+    // Locoの `decimal_len:10:2` のどちらの形式でも受け付ける
+    pub fn parse(mermaid_type: &str) -> Result<Self, DataTypeError> {
+        let (base, constraint, args) = Self::split(mermaid_type)?;
+        // 型は既知だがuniqueを指定できない場合はエラー（`bool^` など）
+        let data_type = Self::new(base, constraint)?;
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        data_type.with_args(&args)
+    }
 
-            LocoDataType::JsonBinaryNullable => "jsonb",
-            LocoDataType::JsonBinary => "jsonb!",
-            LocoDataType::JsonBinaryUnique => "jsonb^",
+    // 型名・修飾子・引数に分ける（引数の数・範囲は確認しない）
+    fn split(mermaid_type: &str) -> Result<(LocoBaseType, Constraint, Vec<String>), DataTypeError> {
+        let lower = mermaid_type.to_lowercase();
+        let mut parts = lower.split(':');
+        let head = parts.next().unwrap_or_default();
+        let mut args: Vec<&str> = parts.collect();
+
+        // `decimal_len(10,2)!` の括弧部分を引数として取り出す（修飾子は括弧の前後どちらでもよい）
+        let (name, suffix) = match head.find(['(', '<']) {
+            Some(open) => {
+                let close = head
+                    .rfind([')', '>'])
+                    .filter(|&close| close > open)
+                    .ok_or_else(|| DataTypeError::InvalidParameters {
+                        type_name: head[..open].trim_end_matches(['!', '^']).to_string(),
+                        reason: "括弧が閉じられていません".to_string(),
+                    })?;
+                args.extend(head[open + 1..close].split(',').map(str::trim));
+                (&head[..open], &head[close + 1..])
+            }
+            None => (head, ""),
+        };
+        let base_name = name.trim_end_matches(['!', '^']);
+        let modifier = format!("{}{}", &name[base_name.len()..], suffix);
+        let constraint: Constraint = modifier
+            .parse()
+            .map_err(|_| DataTypeError::UnknownType(mermaid_type.to_string()))?;
+        let base: LocoBaseType = resolve_alias(base_name)
+            .parse()
+            .map_err(|_| DataTypeError::UnknownType(mermaid_type.to_string()))?;
+        Ok((base, constraint, args.into_iter().map(str::to_string).collect()))
+    }
 
-            LocoDataType::MoneyNullable => "money",
-            LocoDataType::Money => "money!",
-            LocoDataType::MoneyUnique => "money^",
+    // 引数の要らない型は引数が無ければそのまま、`decimal_len` などは引数が無ければエラーにする
+    fn with_args(self, args: &[&str]) -> Result<Self, DataTypeError> {
+        if args.is_empty() && !self.base.requires_params() {
+            Ok(self)
+        } else {
+            self.with_params(args)
+        }
+    }
 
-            LocoDataType::UnsignedNullable => "unsigned",
-            LocoDataType::Unsigned => "unsigned!",
-            LocoDataType::UnsignedUnique => "unsigned^",
+    // 解釈できない型は `string`（null許容）として扱う
+    // uniqueを指定できない型は、null許容にならないよう必須として扱う
+    // 引数が無い・不正な `decimal_len` などは、引数の要らない型（`decimal` / `blob` / `array:string`）にする
+    pub fn from_mermaid_type(mermaid_type: &str) -> Self {
+        let Ok((base, constraint, args)) = Self::split(mermaid_type) else {
+            return Self {
+                base: LocoBaseType::String,
+                constraint: Constraint::Nullable,
+                params: None,
+            };
+        };
+        let constraint = match constraint {
+            Constraint::Unique if !base.supports_unique() => Constraint::Required,
+            constraint => constraint,
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let data_type = Self { base, constraint, params: None };
+        data_type.with_args(&args).unwrap_or_else(|_| data_type.fill_missing_params())
+    }
 
-            LocoDataType::BinaryLenNullable => "binary_len",
-            LocoDataType::BinaryLen => "binary_len!",
-            LocoDataType::BinaryLenUnique => "binary_len^",
+    // 引数が必須の型で引数が無い場合は、引数を取らない最も近い型にする（全ての出力で同じ型を使う）
+    pub fn fill_missing_params(self) -> Self {
+        let (base, params) = match (self.base, self.params) {
+            (LocoBaseType::DecimalLen, None) => (LocoBaseType::Decimal, None),
+            (LocoBaseType::BinaryLen, None) => (LocoBaseType::Blob, None),
+            (LocoBaseType::Array, None) => (LocoBaseType::Array, Some(TypeParams::Element(LocoBaseType::String))),
+            (base, params) => (base, params),
+        };
+        Self { base, constraint: self.constraint, params }
+    }
+}
 
-            LocoDataType::VarBinaryNullable => "var_binary",
-            LocoDataType::VarBinary => "var_binary!",
+impl FromStr for LocoDataType {
+    type Err = DataTypeError;
+
+    // Locoの型指定そのもの（別名は受け付けない）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let head = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+        let name = head.trim_end_matches(['!', '^']);
+        let base = name.parse()?;
+        let constraint = head[name.len()..].parse()?;
+        Self::new(base, constraint)?.with_args(&args)
+    }
+}
 
-            LocoDataType::Array => "array",
-            LocoDataType::ArrayUnique => "array!",
-            LocoDataType::ArrayNullable => "array^",
+impl fmt::Display for LocoDataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.base, self.constraint)?;
+        match &self.params {
+            Some(params) => write!(f, "{}", params),
+            None => Ok(()),
         }
     }
 }
@@ -0,0 +1,142 @@
+use triton::dependency::{topological_order, EdgeSource};
+use triton::generator::LocoGenerator;
+use triton::parser::MermaidParser;
+use triton::types::Schema;
+
+fn parse(content: &str) -> Schema {
+    MermaidParser::new().parse(content).unwrap()
+}
+
+#[test]
+fn test_parents_are_scaffolded_first() {
+    let schema = parse(
+        r#"
+Comment {
+    int id PK
+    int post_id FK
+}
+Post {
+    int id PK
+    int user_id FK
+}
+User {
+    int id PK
+}
+"#,
+    );
+
+    let order = topological_order(&schema);
+    assert_eq!(order.order, vec!["User", "Post", "Comment"]);
+    assert!(order.cycles.is_empty());
+
+    let commands = LocoGenerator::new().generate_commands(&schema);
+    assert!(commands[0].starts_with("cargo loco generate scaffold User"));
+    assert!(commands[1].starts_with("cargo loco generate scaffold Post"));
+    assert!(commands[2].starts_with("cargo loco generate scaffold Comment"));
+}
+
+#[test]
+fn test_relationships_contribute_to_order() {
+    let schema = parse(
+        r#"
+Post {
+    int id PK
+}
+Tag {
+    int id PK
+}
+Category {
+    int id PK
+}
+Post }o--|| Category : "belongs to"
+"#,
+    );
+
+    // 依存の無いエンティティは図の順を保つ
+    assert_eq!(topological_order(&schema).order, vec!["Tag", "Category", "Post"]);
+}
+
+#[test]
+fn test_self_reference_is_not_a_cycle() {
+    let schema = parse("Employee {\n    int id PK\n    int employee_id FK\n}\n");
+
+    let order = topological_order(&schema);
+    assert_eq!(order.order, vec!["Employee"]);
+    assert!(order.cycles.is_empty());
+}
+
+#[test]
+fn test_foreign_key_cycle_is_broken_into_later_migration() {
+    let schema = parse(
+        r#"
+User {
+    int id PK
+    int team_id FK
+}
+Team {
+    int id PK
+    int user_id FK
+}
+User }o--|| Team : "belongs to"
+"#,
+    );
+
+    // FK属性と同じ参照を表すリレーションシップで、同じ循環を二重に報告しない
+    let order = topological_order(&schema);
+    assert_eq!(order.cycles, vec![vec!["User".to_string(), "Team".to_string(), "User".to_string()]]);
+    assert_eq!(order.deferred.len(), 1);
+    assert_eq!(order.deferred[0].child, "User");
+    assert_eq!(order.deferred[0].source, EdgeSource::ForeignKey { attribute: "team_id".to_string() });
+    assert_eq!(order.order, vec!["User", "Team"]);
+
+    let diagnostics = order.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "参照が循環しています: User → Team → User");
+    assert!(diagnostics[0].help.as_deref().unwrap().contains("User.team_id"));
+
+    let commands = LocoGenerator::new().generate_commands(&schema);
+    assert_eq!(commands[0], "cargo loco generate scaffold User --api");
+    assert_eq!(commands[1], "cargo loco generate scaffold Team user:references --api");
+    assert_eq!(commands[2], "cargo loco generate migration AddTeamRefToUser team:references");
+}
+
+#[test]
+fn test_relationship_edge_is_preferred_when_breaking_cycle() {
+    let schema = parse(
+        r#"
+A {
+    int id PK
+    int b_id FK
+}
+B {
+    int id PK
+}
+A ||--o{ B : "has many"
+"#,
+    );
+
+    let order = topological_order(&schema);
+    assert_eq!(order.cycles.len(), 1);
+    assert!(matches!(order.deferred[0].source, EdgeSource::Relationship { .. }));
+    assert_eq!(order.order, vec!["B", "A"]);
+}
+
+#[test]
+fn test_duplicate_entities_do_not_panic() {
+    let schema = parse(
+        r#"
+User {
+    int id PK
+}
+Post {
+    int id PK
+    int user_id FK
+}
+User {
+    string name
+}
+"#,
+    );
+    assert_eq!(schema.entities.len(), 3);
+    assert_eq!(topological_order(&schema).order, vec!["User", "Post"]);
+}
@@ -0,0 +1,68 @@
+use triton::diagnostic::{Diagnostic, DiagnosticReporter, Severity};
+use triton::generator::LocoGenerator;
+use triton::parser::MermaidParser;
+
+#[test]
+fn test_render_parse_error_with_snippet() {
+    let mut parser = MermaidParser::new();
+    let content = "erDiagram\n    User {\n        strnig name email\n    }\n";
+    parser.parse(content).unwrap();
+
+    let mut reporter = DiagnosticReporter::new("model.mermaid");
+    reporter.extend(parser.problems().iter().map(|p| Diagnostic::from_parse_error(p, Severity::Error)));
+
+    let expected = "\
+error: エンティティ 'User' の属性として解釈できません
+ --> model.mermaid:3:9
+  |
+3 |         strnig name email
+  |         ^^^^^^^^^^^^^^^^^
+  = help: 属性は `型名 カラム名 [PK,FK] \"コメント\"` の形式で記述してください
+
+1件のエラー、0件の警告
+";
+    assert_eq!(reporter.render(), expected);
+    assert!(reporter.has_errors());
+}
+
+#[test]
+fn test_collects_all_diagnostics_in_source_order() {
+    let mut parser = MermaidParser::new();
+    let content = r#"erDiagram
+    User {
+        int id PK
+    }
+    Usr ||--o{ Post : "has many"
+    ??? garbage
+"#;
+    let schema = parser.parse(content).unwrap();
+    let generator = LocoGenerator::new();
+
+    let mut reporter = DiagnosticReporter::new("model.mermaid");
+    reporter.extend(generator.validate_relationship_entities(&schema));
+    reporter.extend(parser.problems().iter().map(|p| Diagnostic::from_parse_error(p, Severity::Warning)));
+
+    // 未定義のエンティティ2件と解釈できない行1件
+    assert_eq!(reporter.diagnostics().len(), 3);
+    assert!(!reporter.has_errors());
+
+    let rendered = reporter.render();
+    let usr = rendered.find("エンティティ 'Usr' が定義されていません").unwrap();
+    let garbage = rendered.find("解釈できない行です").unwrap();
+    assert!(usr < garbage);
+
+    // 未定義のエンティティ名そのものに下線を引く
+    assert!(rendered.contains("5 |     Usr ||--o{ Post : \"has many\"\n  |     ^^^\n"));
+    assert!(rendered.ends_with("0件のエラー、3件の警告\n"));
+}
+
+#[test]
+fn test_diagnostic_without_span() {
+    let mut reporter = DiagnosticReporter::new("model.mermaid");
+    reporter.push(Diagnostic::warning("出力が空です").with_help("エンティティを追加してください"));
+
+    assert_eq!(
+        reporter.render(),
+        "warning: 出力が空です\n --> model.mermaid\n  = help: エンティティを追加してください\n\n0件のエラー、1件の警告\n"
+    );
+}
@@ -0,0 +1,173 @@
+use triton::diagnostic::Severity;
+use triton::diff::SchemaDiff;
+use triton::generator::LocoGenerator;
+use triton::parser::MermaidParser;
+use triton::types::Schema;
+
+fn parse(content: &str) -> Schema {
+    let mut parser = MermaidParser::new();
+    parser.parse(content).unwrap()
+}
+
+fn diff_commands(old: &str, new: &str) -> Vec<String> {
+    let (old, new) = (parse(old), parse(new));
+    LocoGenerator::new()
+        .generate_diff_plan(&SchemaDiff::between(&old, &new))
+        .into_iter()
+        .map(|c| c.command)
+        .collect()
+}
+
+const OLD: &str = r#"
+User {
+    int id PK
+    string^ email
+    string nickname
+}
+Legacy {
+    int id PK
+}
+Post {
+    int id PK
+    string! title
+}
+"#;
+
+#[test]
+fn test_diff_emits_only_changes() {
+    let commands = diff_commands(
+        OLD,
+        r#"
+User {
+    int id PK
+    string^ email
+    string first_name
+    int login_count
+    timestamp created_at
+}
+Post {
+    int id PK
+    string! title
+    int user_id FK
+}
+Tag {
+    int id PK
+    string! name
+}
+User ||--o{ Post : writes
+Post }o--o{ Tag : tagged
+"#,
+    );
+
+    assert_eq!(
+        commands,
+        vec![
+            "cargo loco generate scaffold Tag name:string! --api",
+            "cargo loco generate migration AddFirstNameAndLoginCountToUsers first_name:string login_count:int",
+            "cargo loco generate migration AddUserRefToPost user:references",
+            "cargo loco generate migration CreateJoinTablePostAndTag post:references tag:references",
+            "cargo loco generate migration RemoveNicknameFromUsers nickname:string",
+            "cargo loco generate migration DropLegacies",
+        ]
+    );
+}
+
+#[test]
+fn test_identical_diagrams_have_no_diff() {
+    let (old, new) = (parse(OLD), parse(OLD));
+    let diff = SchemaDiff::between(&old, &new);
+    assert!(diff.is_empty());
+    assert!(LocoGenerator::new().generate_diff_plan(&diff).is_empty());
+}
+
+#[test]
+fn test_relationship_direction_and_label_are_ignored() {
+    let base = "User {\n    int id PK\n}\nPost {\n    int id PK\n}\n";
+    let commands = diff_commands(
+        &format!("{}User ||--o{{ Post : writes\n", base),
+        &format!("{}Post }}o--|| User : \"written by\"\n", base),
+    );
+    assert!(commands.is_empty());
+}
+
+#[test]
+fn test_new_relationship_becomes_reference_migration() {
+    let base = "User {\n    int id PK\n}\nPost {\n    int id PK\n}\n";
+    let commands = diff_commands(base, &format!("{}User ||--o{{ Post : writes\n", base));
+    assert_eq!(commands, vec!["cargo loco generate migration AddUserRefToPost user:references"]);
+}
+
+#[test]
+fn test_dropped_tables_are_removed_children_first() {
+    let commands = diff_commands(
+        r#"
+Comment {
+    int id PK
+    int post_id FK
+}
+Post {
+    int id PK
+}
+"#,
+        "",
+    );
+    assert_eq!(
+        commands,
+        vec!["cargo loco generate migration DropComments", "cargo loco generate migration DropPosts"]
+    );
+}
+
+#[test]
+fn test_dropped_tables_are_reported() {
+    let old = parse("Post {\n    int id PK\n}\nOrderItem {\n    int id PK\n}\n");
+    let new = parse("");
+    let diagnostics = SchemaDiff::between(&old, &new).diagnostics();
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "テーブル 'posts' を削除する DropPosts のマイグレーションは空のまま生成されます",
+            "テーブル 'order_items' を削除する DropOrderItems のマイグレーションは空のまま生成されます",
+        ]
+    );
+    assert_eq!(
+        diagnostics[1].help.as_deref(),
+        Some("生成されたマイグレーションの `up` に `drop_table(m, \"order_items\")` を記述してください")
+    );
+}
+
+#[test]
+fn test_unsupported_changes_are_reported() {
+    let old = parse(
+        r#"
+User {
+    int id PK
+    int age
+}
+Post {
+    int id PK
+}
+User ||--o{ Post : writes
+"#,
+    );
+    let new = parse(
+        r#"
+User {
+    int id PK
+    text age
+}
+Post {
+    int id PK
+}
+"#,
+    );
+    let diff = SchemaDiff::between(&old, &new);
+    let diagnostics = diff.diagnostics();
+
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+    assert_eq!(diagnostics[0].message, "User.age の型が int から text に変わっていますが、カラムの変更は生成しません");
+    assert_eq!(diagnostics[1].message, "User と Post のリレーションシップの削除は生成しません");
+    assert!(LocoGenerator::new().generate_diff_plan(&diff).is_empty());
+}
@@ -1,6 +1,5 @@
 use triton::parser::MermaidParser;
 use triton::generator::LocoGenerator;
-use triton::types::*;
 
 #[test]
 fn test_special_characters_in_names() {
@@ -97,5 +96,5 @@ User ||--o{ Friend : "has many followers"
     let commands = generator.generate_commands(&schema);
     
     // コマンドが生成されることを確認
-    assert!(commands.len() > 0);
+    assert!(!commands.is_empty());
 }
\ No newline at end of file
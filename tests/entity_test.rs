@@ -0,0 +1,213 @@
+use triton::entities::{rust_type, EntityGenerator};
+use triton::parser::MermaidParser;
+use triton::types::LocoDataType;
+
+fn entities(content: &str) -> Vec<(String, String)> {
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    EntityGenerator::new()
+        .generate(&schema)
+        .into_iter()
+        .map(|f| (f.path(), f.content))
+        .collect()
+}
+
+fn content<'a>(files: &'a [(String, String)], module: &str) -> &'a str {
+    let path = format!("src/models/_entities/{}.rs", module);
+    &files.iter().find(|(p, _)| *p == path).expect("entity file").1
+}
+
+const BLOG: &str = r#"
+User {
+    int id PK
+    string^ email
+    text bio
+}
+Profile {
+    int id PK
+    string! nickname
+}
+Post {
+    int id PK
+    string! title
+    decimal_len!(10,2) price
+    int author_id FK
+    timestamp created_at
+}
+Tag {
+    int id PK
+    string! name
+}
+Post }o--|| User : "written by"
+User ||--|| Profile : has
+Post }o--o{ Tag : tagged
+"#;
+
+#[test]
+fn test_entity_model_and_belongs_to() {
+    let files = entities(BLOG);
+    let expected = r#"//! `SeaORM` Entity, @generated by triton
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "posts")]
+pub struct Model {
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub title: String,
+    #[sea_orm(column_type = "Decimal(Some((10, 2)))")]
+    pub price: Decimal,
+    pub author_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::AuthorId",
+        to = "super::users::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Users,
+    #[sea_orm(has_many = "super::post_tags::Entity")]
+    PostTags,
+}
+
+impl Related<super::post_tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostTags.def()
+    }
+}
+
+impl Related<super::tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::post_tags::Relation::Tags.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::post_tags::Relation::Posts.def().rev())
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+"#;
+    assert_eq!(content(&files, "posts"), expected);
+}
+
+#[test]
+fn test_has_one_and_has_many() {
+    let files = entities(BLOG);
+    let users = content(&files, "users");
+    assert!(users.contains("    #[sea_orm(unique)]\n    pub email: String,\n"));
+    assert!(users.contains("    #[sea_orm(column_type = \"Text\", nullable)]\n    pub bio: Option<String>,\n"));
+    assert!(users.contains("    #[sea_orm(has_one = \"super::profiles::Entity\")]\n    Profiles,\n"));
+    assert!(users.contains("    #[sea_orm(has_many = \"super::posts::Entity\")]\n    Posts,\n"));
+
+    // 1対1の子はリレーションシップから参照カラムを持つ
+    let profiles = content(&files, "profiles");
+    assert!(profiles.contains("    pub user_id: i32,\n"));
+    assert!(profiles.contains("from = \"Column::UserId\""));
+}
+
+#[test]
+fn test_join_table_entity() {
+    let files = entities(BLOG);
+    let post_tags = content(&files, "post_tags");
+    assert!(post_tags.contains(
+        "    #[sea_orm(primary_key, auto_increment = false)]\n    pub post_id: i32,\n    #[sea_orm(primary_key, auto_increment = false)]\n    pub tag_id: i32,\n"
+    ));
+    assert!(post_tags.contains("    Posts,\n"));
+    assert!(post_tags.contains("    Tags,\n"));
+
+    let tags = content(&files, "tags");
+    assert!(tags.contains("        super::post_tags::Relation::Posts.def()\n"));
+    assert!(tags.contains("        Some(super::post_tags::Relation::Tags.def().rev())\n"));
+}
+
+#[test]
+fn test_references_to_uuid_keys_are_uuid() {
+    let files = entities(
+        r#"
+Team {
+    uuid id PK
+}
+User {
+    int id PK
+}
+User }o--|| Team : "belongs to"
+User }o--o{ Team : follows
+"#,
+    );
+    assert!(content(&files, "users").contains("    pub team_id: Uuid,\n"));
+    assert!(content(&files, "user_teams").contains("    pub team_id: Uuid,\n"));
+    assert!(content(&files, "user_teams").contains("    pub user_id: i32,\n"));
+}
+
+#[test]
+fn test_mod_and_prelude() {
+    let files = entities(BLOG);
+    assert_eq!(
+        content(&files, "mod"),
+        "//! `SeaORM` Entity, @generated by triton\n\npub mod prelude;\n\npub mod post_tags;\npub mod posts;\npub mod profiles;\npub mod tags;\npub mod users;\n"
+    );
+    assert!(content(&files, "prelude").contains("pub use super::post_tags::Entity as PostTags;\n"));
+}
+
+#[test]
+fn test_ambiguous_and_self_references() {
+    let files = entities(
+        r#"
+User {
+    int id PK
+    int manager_id FK
+}
+Message {
+    int id PK
+    int sender_id FK
+    int recipient_id FK
+    float score
+}
+Message }o--|| User : "sent by"
+Message }o--|| User : "sent to"
+User }o--|| User : "managed by"
+"#,
+    );
+    let users = content(&files, "users");
+    assert!(users.contains("        belongs_to = \"Entity\",\n        from = \"Column::ManagerId\",\n        to = \"Column::Id\","));
+    assert!(users.contains("    SelfRef,\n"));
+    // 同じテーブルから2つの参照があると向きが決まらないので has_many は出さない
+    assert!(!users.contains("has_many"));
+
+    let messages = content(&files, "messages");
+    assert!(messages.contains("#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]"));
+    assert!(messages.contains("    UsersSenderId,\n"));
+    assert!(messages.contains("    UsersRecipientId,\n"));
+    assert!(!messages.contains("impl Related"));
+}
+
+#[test]
+fn test_rust_types() {
+    let rust = |t: &str| rust_type(&LocoDataType::parse(t).unwrap());
+    assert_eq!(rust("uuid"), ("Uuid".to_string(), None));
+    assert_eq!(rust("big_unsigned"), ("u64".to_string(), None));
+    assert_eq!(rust("tstz"), ("DateTimeWithTimeZone".to_string(), None));
+    assert_eq!(rust("binary_len(16)"), ("Vec<u8>".to_string(), Some("Binary(16)".to_string())));
+    assert_eq!(rust("jsonb"), ("Json".to_string(), Some("JsonBinary".to_string())));
+    assert_eq!(rust("array<int>"), ("Vec<i32>".to_string(), None));
+
+    // キーワードのカラム名は生識別子にする
+    let files = entities("Item {\n    int id PK\n    string type\n    int! match\n    string self\n}\n");
+    let items = content(&files, "items");
+    assert!(items.contains("    pub r#type: Option<String>,\n"));
+    assert!(items.contains("    pub r#match: i32,\n"));
+    assert!(items.contains("    #[sea_orm(column_name = \"self\")]\n    pub self_: Option<String>,\n"));
+}
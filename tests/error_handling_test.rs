@@ -1,3 +1,5 @@
+use triton::error::ParseError;
+use triton::generator::LocoGenerator;
 use triton::parser::MermaidParser;
 
 #[test]
@@ -59,4 +61,216 @@ User -- Post
     assert_eq!(schema.entities.len(), 2);
     // 不正なリレーションシップ形式は無視される
     assert_eq!(schema.relationships.len(), 0);
-}
\ No newline at end of file
+}
+#[test]
+fn test_lenient_mode_records_problems() {
+    let mut parser = MermaidParser::new();
+    let content = r#"erDiagram
+User {
+    int id PK
+    invalid_attribute_format
+}
+User -- Post
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    assert_eq!(schema.entities.len(), 1);
+
+    // エラーにはならないが、無視した行は記録される
+    let problems = parser.problems();
+    assert_eq!(problems.len(), 2);
+    assert!(matches!(&problems[0], ParseError::InvalidAttribute { entity, .. } if entity == "User"));
+    assert!(matches!(&problems[1], ParseError::UnrecognizedLine { .. }));
+}
+
+#[test]
+fn test_strict_mode_reports_every_line() {
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    let content = r#"erDiagram
+    User {
+        int id PK
+        strnig name email
+    }
+
+    Post {
+        int id PK
+
+    User ||--o{ Post : "has many"
+    Usr -> Post
+"#;
+
+    let errors = parser.parse(content).unwrap_err();
+    let errors = errors.errors();
+    assert_eq!(errors.len(), 4);
+
+    // 属性の誤り: 行・列・該当行を保持する
+    let span = errors[0].span();
+    assert!(matches!(&errors[0], ParseError::InvalidAttribute { entity, .. } if entity == "User"));
+    assert_eq!(span.line, 4);
+    assert_eq!(span.column, 9);
+    assert_eq!(span.snippet, "        strnig name email");
+
+    // 閉じられていないブロック内の行も属性エラーになる
+    assert!(matches!(&errors[1], ParseError::InvalidAttribute { entity, .. } if entity == "Post"));
+    assert_eq!(errors[1].span().line, 10);
+    assert_eq!(errors[2].span().line, 11);
+
+    // `{` の位置を指す
+    assert!(matches!(&errors[3], ParseError::UnclosedEntity { entity, .. } if entity == "Post"));
+    assert_eq!(errors[3].span().line, 7);
+    assert_eq!(errors[3].span().column, 10);
+}
+
+#[test]
+fn test_strict_mode_accepts_valid_diagram() {
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    let content = r#"erDiagram
+    User {
+        int id PK
+        string name
+    }
+
+    Post {
+        int id PK
+        int user_id FK
+    }
+
+    User ||--o{ Post : "has many"
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    assert_eq!(schema.entities.len(), 2);
+    assert!(parser.problems().is_empty());
+}
+
+#[test]
+fn test_parse_error_display() {
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    let errors = parser.parse("erDiagram\n  garbage line\n").unwrap_err();
+
+    assert_eq!(errors.to_string(), "2行3列目: 解釈できない行です: garbage line");
+}
+
+#[test]
+fn test_unknown_type_is_reported() {
+    let content = r#"erDiagram
+Event {
+    int id PK
+    interval duration
+}
+"#;
+
+    // lenientモードでは string として扱い、問題として記録する
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    assert_eq!(schema.entities[0].attributes[1].data_type, "string");
+
+    let problems = parser.problems();
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].message(), "Event.duration の型 'interval' はLocoの型に変換できません");
+    assert_eq!(problems[0].span().line, 4);
+    assert_eq!(problems[0].span().column, 5);
+    assert_eq!(problems[0].span().length, 8);
+
+    // strictモードではエラーになる
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    let errors = parser.parse(content).unwrap_err();
+    assert!(matches!(
+        &errors.errors()[0],
+        ParseError::UnknownType { entity, attribute, type_name, .. }
+            if entity == "Event" && attribute == "duration" && type_name == "interval"
+    ));
+}
+
+#[test]
+fn test_invalid_type_parameters_are_reported() {
+    let content = r#"erDiagram
+Product {
+    int id PK
+    decimal_len(70,2)! price
+}
+"#;
+
+    // lenientモードでは引数の要らない型として扱う
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    assert_eq!(schema.entities[0].attributes[1].data_type, "decimal!");
+
+    let problems = parser.problems();
+    assert_eq!(problems.len(), 1);
+    assert_eq!(
+        problems[0].message(),
+        "Product.price: 'decimal_len' の引数が不正です: 桁数は1〜65で指定してください（70）"
+    );
+    assert_eq!(problems[0].span().column, 5);
+    assert_eq!(problems[0].span().length, "decimal_len(70,2)!".len());
+
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    assert!(matches!(
+        &parser.parse(content).unwrap_err().errors()[0],
+        ParseError::InvalidTypeParameters { attribute, .. } if attribute == "price"
+    ));
+}
+
+#[test]
+fn test_missing_type_parameters_are_reported() {
+    let content = r#"erDiagram
+Product {
+    int id PK
+    decimal_len price
+    binary_len! checksum
+    array tags
+}
+"#;
+
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    let types: Vec<&str> = schema.entities[0].attributes.iter().map(|a| a.data_type.as_str()).collect();
+    assert_eq!(types, vec!["int", "decimal", "blob!", "array:string"]);
+    assert_eq!(parser.problems().len(), 3);
+    assert_eq!(
+        parser.problems()[0].message(),
+        "Product.price: 'decimal_len' の引数が不正です: `decimal_len(桁数,小数点以下の桁数)` の形式で指定してください"
+    );
+    assert_eq!(
+        LocoGenerator::new().generate_commands(&schema),
+        vec!["cargo loco generate scaffold Product price:decimal checksum:blob! tags:array:string --api"]
+    );
+
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    assert_eq!(parser.parse(content).unwrap_err().errors().len(), 3);
+}
+
+#[test]
+fn test_unsupported_unique_is_reported() {
+    let content = r#"erDiagram
+Flag {
+    int id PK
+    bool^ active
+    json data UK
+}
+"#;
+
+    // lenientモードでは必須として扱い、問題として記録する
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    let attrs = &schema.entities[0].attributes;
+    assert_eq!(attrs[1].data_type, "bool!");
+    assert!(!attrs[1].is_unique);
+    assert!(!attrs[1].is_nullable);
+    assert_eq!(attrs[2].data_type, "json!");
+
+    let problems = parser.problems();
+    assert_eq!(problems.len(), 2);
+    assert_eq!(problems[0].message(), "Flag.active の型 'bool' にはユニーク制約を指定できません");
+    assert_eq!(problems[0].span().column, 5);
+    assert_eq!(problems[0].span().length, 5);
+
+    // strictモードではエラーになる
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    let errors = parser.parse(content).unwrap_err();
+    assert!(matches!(
+        &errors.errors()[0],
+        ParseError::UnsupportedConstraint { entity, attribute, .. } if entity == "Flag" && attribute == "active"
+    ));
+}
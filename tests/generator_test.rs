@@ -0,0 +1,489 @@
+use triton::diagnostic::Severity;
+use triton::generator::{LocoGenerator, ManagedColumn, ScaffoldKind};
+use triton::parser::MermaidParser;
+
+fn generate(content: &str) -> Vec<String> {
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    LocoGenerator::new().generate_commands(&schema)
+}
+
+#[test]
+fn test_reference_goes_to_many_side() {
+    let content = r#"
+Post {
+    int id PK
+}
+
+Category {
+    int id PK
+}
+
+Post }o--|| Category : "belongs to"
+"#;
+
+    let commands = generate(content);
+
+    assert!(commands.contains(&"cargo loco generate migration AddCategoryRefToPost category:references".to_string()));
+    assert!(!commands.iter().any(|c| c.contains("post:references")));
+}
+
+#[test]
+fn test_reference_direction_is_independent_of_writing_order() {
+    let forward = generate("User ||--o{ Post : \"has many\"");
+    let backward = generate("Post }|--|| User : \"written by\"");
+
+    let expected = "cargo loco generate migration AddUserRefToPost user:references".to_string();
+    assert!(forward.contains(&expected));
+    assert!(backward.contains(&expected));
+}
+
+#[test]
+fn test_one_to_one_adds_reference_to_optional_side() {
+    let commands = generate("User ||--|| Profile : has\nAccount |o--|| Owner : \"owned by\"");
+
+    assert_eq!(
+        commands,
+        vec![
+            "cargo loco generate migration AddUserRefToProfile user:references",
+            "cargo loco generate migration AddOwnerRefToAccount owner:references",
+        ]
+    );
+}
+
+#[test]
+fn test_reference_names_are_snake_case() {
+    let commands = generate("UserProfile ||--o{ BlogPost : \"writes\"\nBlogPost }o--o{ Tag : \"tagged\"");
+
+    assert!(commands.contains(&"cargo loco generate migration AddUserProfileRefToBlogPost user_profile:references".to_string()));
+    assert!(commands.contains(
+        &"cargo loco generate migration CreateJoinTableBlogPostAndTag blog_post:references tag:references".to_string()
+    ));
+}
+
+#[test]
+fn test_foreign_key_columns_strip_id_suffix() {
+    let commands = generate(
+        r#"
+User {
+    int id PK
+}
+Post {
+    int id PK
+    int user_id FK
+}
+"#,
+    );
+
+    assert!(commands.contains(&"cargo loco generate scaffold Post user:references --api".to_string()));
+    assert!(!commands.iter().any(|c| c.contains("user_id:references")));
+}
+
+#[test]
+fn test_foreign_key_target_from_relationship() {
+    let commands = generate(
+        r#"
+User {
+    int id PK
+}
+Post {
+    int id PK
+    int author_id FK
+}
+User ||--o{ Post : "writes"
+"#,
+    );
+
+    // カラム名が参照先と対応しないので参照先テーブルを明示する
+    assert!(commands.contains(&"cargo loco generate scaffold Post author:references:users --api".to_string()));
+}
+
+#[test]
+fn test_foreign_key_target_from_annotation() {
+    let commands = generate(
+        r#"
+Category {
+    int id PK
+}
+User {
+    int id PK
+}
+Post {
+    int id PK
+    %% triton: references=Category
+    int parent_id FK
+    %% triton: references=User
+    int user_id FK
+}
+"#,
+    );
+
+    assert!(commands.contains(&"cargo loco generate scaffold Post parent:references:categories user:references --api".to_string()));
+}
+
+#[test]
+fn test_unresolved_foreign_key_falls_back_to_column_name() {
+    let commands = generate("Post {\n    int id PK\n    int owner_id FK\n}\n");
+
+    assert_eq!(commands, vec!["cargo loco generate scaffold Post owner:references --api".to_string()]);
+}
+
+#[test]
+fn test_foreign_key_attribute_replaces_relationship_migration() {
+    let content = r#"
+User {
+    int id PK
+}
+Post {
+    int id PK
+    int user_id FK
+}
+User ||--o{ Post : "has many"
+"#;
+    let commands = generate(content);
+
+    assert_eq!(
+        commands,
+        vec![
+            "cargo loco generate scaffold User --api".to_string(),
+            "cargo loco generate scaffold Post user:references --api".to_string(),
+        ]
+    );
+
+    let schema = MermaidParser::new().parse(content).unwrap();
+    assert!(LocoGenerator::new().validate_foreign_keys(&schema).is_empty());
+}
+
+#[test]
+fn test_duplicate_relationships_generate_one_reference() {
+    let content = r#"
+User ||--o{ Post : "writes"
+Post }o--|| User : "written by"
+Post }o--o{ Tag : "tagged"
+Tag }o--o{ Post : "tags"
+"#;
+    let commands = generate(content);
+
+    let references: Vec<_> = commands.iter().filter(|c| c.contains("migration")).collect();
+    assert_eq!(
+        references,
+        vec![
+            "cargo loco generate migration AddUserRefToPost user:references",
+            "cargo loco generate migration CreateJoinTablePostAndTag post:references tag:references",
+        ]
+    );
+
+    // 逆向きの記述は同じリレーションシップとみなし、警告しない
+    let schema = MermaidParser::new().parse(content).unwrap();
+    assert!(LocoGenerator::new().validate_duplicate_relationships(&schema).is_empty());
+}
+
+#[test]
+fn test_relationships_in_the_same_direction_are_reported() {
+    let content = r#"
+User ||--o{ Post : "writes"
+User ||--o{ Post : "edits"
+Post }o--|| User : "written by"
+Post }o--o{ Tag : "tagged"
+Post }o--o{ Tag : "featured"
+"#;
+    let schema = MermaidParser::new().parse(content).unwrap();
+    let diagnostics = LocoGenerator::new().validate_duplicate_relationships(&schema);
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "User と Post のリレーションシップが同じ向きで複数記述されています",
+            "Post と Tag のリレーションシップが同じ向きで複数記述されています",
+        ]
+    );
+    assert_eq!(diagnostics[0].span.as_ref().unwrap().line, 3);
+    assert!(diagnostics[0].help.as_deref().unwrap().starts_with("参照は1つ（Post.user_id）だけ生成します"));
+
+    // FK属性で参照カラムを書き分けていれば警告しない
+    let schema = MermaidParser::new()
+        .parse(
+            r#"
+User {
+    int id PK
+}
+Post {
+    int id PK
+    %% triton: references=User
+    int author_id FK
+    %% triton: references=User
+    int editor_id FK
+}
+User ||--o{ Post : "writes"
+User ||--o{ Post : "edits"
+"#,
+        )
+        .unwrap();
+    assert!(LocoGenerator::new().validate_duplicate_relationships(&schema).is_empty());
+}
+
+#[test]
+fn test_inconsistent_foreign_keys_are_reported() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"
+User {
+    int id PK
+    int post_id FK
+}
+Post {
+    int id PK
+}
+Tag {
+    int id PK
+    int user_id FK
+    int owner_id FK
+}
+User ||--o{ Post : "has many"
+"#,
+        )
+        .unwrap();
+
+    let diagnostics = LocoGenerator::new().validate_foreign_keys(&schema);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "User.post_id は Post を参照していますが、リレーションシップでは User が参照する側になっていません",
+            "Tag.user_id は User を参照していますが、リレーションシップが記述されていません",
+            "Tag.owner_id の参照先エンティティが見つかりません",
+        ]
+    );
+    assert_eq!(diagnostics[0].span.as_ref().unwrap().line, 14);
+}
+
+#[test]
+fn test_loco_managed_columns_are_omitted() {
+    let commands = generate(
+        r#"
+Post {
+    int id PK
+    string title
+    timestamp created_at
+    tstz updated_at
+}
+"#,
+    );
+
+    assert_eq!(commands, vec!["cargo loco generate scaffold Post title:string --api"]);
+}
+
+#[test]
+fn test_managed_columns_are_configurable() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"
+Post {
+    int id PK
+    string title
+    int version
+    timestamp created_at
+}
+"#,
+        )
+        .unwrap();
+
+    let generator = LocoGenerator::new().with_managed_columns(vec![ManagedColumn::new("version", &["int"])]);
+    let commands = generator.generate_commands(&schema);
+
+    assert_eq!(
+        commands,
+        vec!["cargo loco generate scaffold Post title:string created_at:date_time --api"]
+    );
+}
+
+#[test]
+fn test_managed_column_type_conflicts_are_reported() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"
+Post {
+    uuid id PK
+    string title
+    date created_at
+    date_time updated_at
+}
+"#,
+        )
+        .unwrap();
+
+    let diagnostics = LocoGenerator::new().validate_managed_columns(&schema);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "Post.id は uuid 型で宣言されていますが、Locoは int 型で作成します",
+            "Post.created_at は date 型で宣言されていますが、Locoは tstz 型で作成します",
+        ]
+    );
+}
+
+#[test]
+fn test_parameterized_types_in_scaffold() {
+    let commands = generate(
+        r#"
+Product {
+    int id PK
+    decimal_len(10,2)! price
+    binary_len(16) checksum
+    array<string> tags
+}
+"#,
+    );
+
+    assert_eq!(
+        commands,
+        vec!["cargo loco generate scaffold Product price:decimal_len!:10:2 checksum:binary_len:16 tags:array:string --api"]
+    );
+}
+
+#[test]
+fn test_scaffold_kind_per_entity() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"
+%% triton: scaffold=htmx
+Post {
+    int id PK
+    string title
+}
+%% triton: scaffold=model
+Tag {
+    int id PK
+    string name
+}
+Comment {
+    int id PK
+    text body
+}
+"#,
+        )
+        .unwrap();
+
+    // 個別指定 → 注釈 → 既定 の順に優先する
+    let generator = LocoGenerator::new()
+        .with_scaffold_kind(ScaffoldKind::Html)
+        .with_scaffold_override("Post", ScaffoldKind::Api);
+
+    assert_eq!(
+        generator.generate_commands(&schema),
+        vec![
+            "cargo loco generate scaffold Post title:string --api",
+            "cargo loco generate model Tag name:string",
+            "cargo loco generate scaffold Comment body:text --html",
+        ]
+    );
+}
+
+#[test]
+fn test_controller_only_entities() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"
+%% triton: scaffold=controller
+Dashboard {
+    int id PK
+    string title
+}
+User {
+    int id PK
+}
+%% triton: scaffold=controller
+Post {
+    int id PK
+}
+User ||--o{ Post : writes
+"#,
+        )
+        .unwrap();
+
+    let generator = LocoGenerator::new();
+    let plan = generator.generate_plan(&schema);
+    let dashboard = plan.iter().find(|c| c.command.contains("Dashboard")).unwrap();
+    assert_eq!(dashboard.command, "cargo loco generate controller Dashboard --api");
+    assert_eq!(dashboard.model, None);
+
+    let messages: Vec<String> = generator.validate_scaffold_kinds(&schema).into_iter().map(|d| d.message).collect();
+    assert_eq!(
+        messages,
+        vec!["Post は controller として生成するためテーブルを作成しませんが、参照に含まれています"]
+    );
+}
+
+#[test]
+fn test_invalid_scaffold_kinds_are_reported() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"
+%% triton: scaffold=graphql
+Post {
+    int id PK
+}
+"#,
+        )
+        .unwrap();
+
+    let generator = LocoGenerator::new().with_scaffold_override("Tag", ScaffoldKind::Model);
+    let messages: Vec<String> = generator
+        .validate_scaffold_kinds(&schema)
+        .into_iter()
+        .map(|d| d.message)
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "Post の scaffold 注釈: 'graphql' は生成方法ではありません（api・html・htmx・model・controller のいずれか）",
+            "生成方法を指定したエンティティ 'Tag' は定義されていません",
+        ]
+    );
+    assert_eq!(
+        generator.generate_commands(&schema),
+        vec!["cargo loco generate scaffold Post --api"]
+    );
+}
+
+#[test]
+fn test_entities_without_ascii_names_are_reported() {
+    let schema = MermaidParser::new()
+        .parse(
+            r#"
+"ユーザー" {
+    int id PK
+    string name
+}
+u["ユーザー"] {
+    int id PK
+}
+"#,
+        )
+        .unwrap();
+
+    let diagnostics = LocoGenerator::new().validate_model_names(&schema);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].message, "エンティティ 'ユーザー' の名前からモデル名を作れません");
+    assert_eq!(diagnostics[0].help.as_deref(), Some("`u[\"ユーザー\"]` のように、ASCIIの英数字の識別子を付けてください"));
+
+    // モデル名は空にならない
+    assert_eq!(schema.entities[0].model_name(), "Entity");
+    assert_eq!(schema.entities[1].model_name(), "u");
+    assert_eq!(
+        LocoGenerator::new().generate_commands(&schema)[0],
+        "cargo loco generate scaffold Entity name:string --api"
+    );
+}
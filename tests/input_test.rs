@@ -0,0 +1,179 @@
+use triton::diagnostic::Severity;
+use triton::generator::LocoGenerator;
+use triton::input::{self, InputFormat};
+use triton::parser::MermaidParser;
+
+#[test]
+fn test_format_from_path() {
+    assert_eq!(InputFormat::from_path("schema.mermaid"), InputFormat::Mermaid);
+    assert_eq!(InputFormat::from_path("docs/design.md"), InputFormat::Markdown);
+    assert_eq!(InputFormat::from_path("schema.JSON"), InputFormat::Json);
+    assert_eq!(InputFormat::from_path("schema.yml"), InputFormat::Yaml);
+    assert_eq!("yaml".parse::<InputFormat>(), Ok(InputFormat::Yaml));
+    assert!("toml".parse::<InputFormat>().is_err());
+}
+
+#[test]
+fn test_json_schema_matches_mermaid() {
+    let mut parser = MermaidParser::new();
+    let from_mermaid = parser
+        .parse(
+            r#"erDiagram
+    User {
+        int id PK
+        string^ email
+    }
+    Post {
+        int id PK
+        int user_id FK
+    }
+    User ||--o{ Post : writes
+"#,
+        )
+        .unwrap();
+
+    // `--emit json` の出力はそのまま入力に使える
+    let json = serde_json::to_string_pretty(&from_mermaid).unwrap();
+    let from_json = input::parse_json(&json).unwrap();
+
+    let generator = LocoGenerator::new();
+    assert_eq!(generator.generate_commands(&from_json), generator.generate_commands(&from_mermaid));
+}
+
+#[test]
+fn test_yaml_schema_with_defaults_and_aliases() {
+    let mut schema = input::parse_yaml(
+        r#"
+entities:
+  - name: Product
+    attributes:
+      - { name: id, data_type: int, is_primary_key: true }
+      - { name: title, data_type: "VARCHAR!" }
+      - { name: price, data_type: "decimal_len(10,2)" }
+      - { name: period, data_type: interval }
+"#,
+    )
+    .unwrap();
+
+    let diagnostics = input::normalize_data_types(&mut schema, Severity::Warning);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].message, "Product.period の型 'interval' はLocoの型に変換できません");
+
+    assert_eq!(
+        LocoGenerator::new().generate_commands(&schema),
+        vec!["cargo loco generate scaffold Product title:string! price:decimal_len:10:2 period:string --api"]
+    );
+}
+
+#[test]
+fn test_syntax_errors_point_at_location() {
+    let diagnostic = input::parse_json("{\"entities\": [\n  {\"name\": 1}\n]}").unwrap_err();
+    let span = diagnostic.span.unwrap();
+
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert!(diagnostic.message.starts_with("JSONのスキーマとして読み込めません"));
+    assert_eq!((span.line, span.column), (2, 12));
+    assert_eq!(span.snippet, "  {\"name\": 1}");
+}
+
+#[test]
+fn test_duplicate_entities_in_mermaid_are_merged() {
+    let mut schema = MermaidParser::new()
+        .parse(
+            r#"erDiagram
+    User {
+        int id PK
+        string name
+    }
+    User {
+        string^ email
+        int name
+    }
+"#,
+        )
+        .unwrap();
+    let diagnostics = input::merge_duplicate_entities(&mut schema, Severity::Warning);
+
+    assert_eq!(schema.entities.len(), 1);
+    let names: Vec<&str> = schema.entities[0].attributes.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "name", "email"]);
+    assert_eq!(diagnostics[0].message, "エンティティ 'User' が複数回定義されています");
+    assert_eq!(diagnostics[1].message, "User.name の定義が食い違っています（最初の定義 'string' を使います）");
+    assert_eq!(
+        LocoGenerator::new().generate_commands(&schema),
+        vec!["cargo loco generate scaffold User name:string email:string^ --api"]
+    );
+}
+
+#[test]
+fn test_duplicate_entities_in_json_are_merged() {
+    let mut schema = input::parse_json(
+        r#"{"entities": [
+            {"name": "User", "attributes": [{"name": "id", "data_type": "int", "is_primary_key": true}]},
+            {"name": "User", "attributes": [{"name": "email", "data_type": "string"}]}
+        ]}"#,
+    )
+    .unwrap();
+    let diagnostics = input::merge_duplicate_entities(&mut schema, Severity::Error);
+
+    assert_eq!(schema.entities.len(), 1);
+    assert_eq!(schema.entities[0].attributes.len(), 2);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
+
+#[test]
+fn test_duplicate_entities_across_markdown_blocks_are_merged() {
+    let document = "# 概要\n\n```mermaid\nerDiagram\n    User {\n        int id PK\n    }\n```\n\n# 詳細\n\n```mermaid\nerDiagram\n    User {\n        string name\n    }\n    Post {\n        int user_id FK\n    }\n```\n";
+    let mut schema = triton::types::Schema {
+        entities: Vec::new(),
+        relationships: Vec::new(),
+    };
+    for block in triton::markdown::extract_er_diagrams(document) {
+        let parsed = MermaidParser::new().parse(&block.content).unwrap();
+        schema.entities.extend(parsed.entities);
+    }
+    input::merge_duplicate_entities(&mut schema, Severity::Warning);
+
+    assert_eq!(schema.entities.len(), 2);
+    let commands = LocoGenerator::new().generate_commands(&schema);
+    assert_eq!(commands[0], "cargo loco generate scaffold User name:string --api");
+    assert_eq!(commands[1], "cargo loco generate scaffold Post user:references --api");
+}
+
+#[test]
+fn test_flags_are_folded_into_the_constraint() {
+    let mut schema = input::parse_yaml(
+        r#"
+entities:
+  - name: User
+    attributes:
+      - { name: id, data_type: int, is_primary_key: true }
+      - { name: email, data_type: string, is_unique: true }
+      - { name: active, data_type: bool, is_unique: true }
+      - { name: name, data_type: "string!", is_nullable: true }
+      - { name: bio, data_type: text }
+"#,
+    )
+    .unwrap();
+
+    let diagnostics = input::normalize_data_types(&mut schema, Severity::Warning);
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "User.active の型 'bool' にはユニーク制約を指定できません",
+            "User.name は is_nullable ですが型 'string!' はnullを許容しません（型の修飾子を使います）",
+        ]
+    );
+
+    let attrs = &schema.entities[0].attributes;
+    assert!(attrs[1].is_unique && !attrs[1].is_nullable);
+    assert!(!attrs[2].is_unique && !attrs[2].is_nullable);
+    assert!(!attrs[3].is_nullable);
+    assert!(attrs[4].is_nullable);
+    assert_eq!(
+        LocoGenerator::new().generate_commands(&schema),
+        vec!["cargo loco generate scaffold User email:string^ active:bool! name:string! bio:text --api"]
+    );
+}
@@ -0,0 +1,67 @@
+use triton::parser::MermaidParser;
+use triton::types::Schema;
+
+#[test]
+fn test_schema_round_trips_through_json() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"erDiagram
+    %% triton: scaffold=api
+    "Order Item"[OrderItem] {
+        int id PK
+        decimal_len(10,2)! price "税込価格"
+        %% triton: references=Order
+        int order_id FK
+    }
+    Order {
+        int id PK
+    }
+    Order ||--o{ "Order Item" : contains
+"#,
+        )
+        .unwrap();
+
+    let json = serde_json::to_string(&schema).unwrap();
+    let restored: Schema = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored, schema);
+}
+
+#[test]
+fn test_json_layout() {
+    let mut parser = MermaidParser::new();
+    let schema = parser
+        .parse(
+            r#"erDiagram
+    User {
+        int id PK
+    }
+    User ||--o{ Post : writes
+"#,
+        )
+        .unwrap();
+
+    let value = serde_json::to_value(&schema).unwrap();
+
+    // 値の無い項目は出力しない
+    assert_eq!(
+        value["entities"][0],
+        serde_json::json!({
+            "name": "User",
+            "attributes": [{
+                "name": "id",
+                "data_type": "int",
+                "is_primary_key": true,
+                "is_foreign_key": false,
+                "is_unique": false,
+                "is_nullable": false,
+            }],
+        })
+    );
+    let relationship = &value["relationships"][0];
+    assert_eq!(relationship["relationship_type"], "one_to_many");
+    assert_eq!(relationship["from_cardinality"], "one");
+    assert_eq!(relationship["to_cardinality"], "zero_or_many");
+    assert_eq!(relationship["span"]["line"], 5);
+}
@@ -0,0 +1,102 @@
+use triton::markdown::{extract_er_diagrams, is_markdown_path, BlockSelector};
+use triton::parser::MermaidParser;
+
+const DESIGN_DOC: &str = r#"# 設計メモ
+
+本文中の User { のような行はスキーマに含めない。
+User ||--o{ Post : "prose"
+
+## ブログ
+
+```mermaid
+erDiagram
+    User {
+        int id PK
+        string name
+    }
+```
+
+```mermaid
+sequenceDiagram
+    Alice->>Bob: Hello
+```
+
+```rust
+Comment {
+    int id PK
+}
+```
+
+## 管理画面
+
+~~~mermaid
+erDiagram
+    Admin {
+        int id PK
+    }
+~~~
+"#;
+
+#[test]
+fn test_extracts_only_er_diagram_blocks() {
+    let blocks = extract_er_diagrams(DESIGN_DOC);
+
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].index, 0);
+    assert_eq!(blocks[0].heading.as_deref(), Some("ブログ"));
+    assert_eq!(blocks[1].index, 1);
+    assert_eq!(blocks[1].heading.as_deref(), Some("管理画面"));
+    assert!(blocks[1].content.contains("Admin {"));
+}
+
+#[test]
+fn test_prose_never_leaks_into_schema() {
+    let blocks = extract_er_diagrams(DESIGN_DOC);
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(&blocks[0].content).unwrap();
+
+    assert_eq!(schema.entities.len(), 1);
+    assert_eq!(schema.entities[0].name, "User");
+    assert!(schema.relationships.is_empty());
+}
+
+#[test]
+fn test_block_selector() {
+    let blocks = extract_er_diagrams(DESIGN_DOC);
+
+    let by_index = BlockSelector::parse("1");
+    assert_eq!(by_index, BlockSelector::Index(1));
+    assert!(by_index.matches(&blocks[1]));
+    assert!(!by_index.matches(&blocks[0]));
+
+    let by_heading = BlockSelector::parse("ブログ");
+    assert!(by_heading.matches(&blocks[0]));
+    assert!(!by_heading.matches(&blocks[1]));
+}
+
+#[test]
+fn test_line_offset_points_into_markdown() {
+    let blocks = extract_er_diagrams("# Doc\n\n```mermaid\nerDiagram\n    User {\n        garbage\n    }\n```\n");
+    let mut parser = MermaidParser::new().with_line_offset(blocks[0].line_offset);
+    parser.parse(&blocks[0].content).unwrap();
+
+    // Markdownファイル上の6行目を指す
+    assert_eq!(parser.problems()[0].span().line, 6);
+}
+
+#[test]
+fn test_sample_markdown_matches_mermaid_file() {
+    let markdown = std::fs::read_to_string("sample/erDiagram.md").unwrap();
+    let mermaid = std::fs::read_to_string("sample/erDiagram.mermaid").unwrap();
+
+    let blocks = extract_er_diagrams(&markdown);
+    assert_eq!(blocks.len(), 1);
+
+    let from_markdown = MermaidParser::new().parse(&blocks[0].content).unwrap();
+    let from_mermaid = MermaidParser::new().parse(&mermaid).unwrap();
+    assert_eq!(from_markdown.entities.len(), from_mermaid.entities.len());
+    assert_eq!(from_markdown.relationships.len(), from_mermaid.relationships.len());
+
+    assert!(is_markdown_path("sample/erDiagram.md"));
+    assert!(!is_markdown_path("sample/erDiagram.mermaid"));
+}
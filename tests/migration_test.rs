@@ -0,0 +1,196 @@
+use triton::migration::{col_type, migration_action, register_migrations, MigrationGenerator};
+use triton::parser::MermaidParser;
+use triton::types::LocoDataType;
+
+// 2025-01-02 03:04:05 UTC
+const TIMESTAMP: u64 = 1_735_787_045;
+
+fn migrations(content: &str) -> Vec<(String, String)> {
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    MigrationGenerator::new()
+        .with_timestamp(TIMESTAMP)
+        .generate(&schema)
+        .into_iter()
+        .map(|f| (f.path(), f.content))
+        .collect()
+}
+
+#[test]
+fn test_create_table_migration() {
+    let files = migrations(
+        r#"
+User {
+    int id PK
+    string^ email
+}
+Post {
+    int id PK
+    string! title
+    decimal_len(10,2) price
+    int author_id FK
+    timestamp created_at
+}
+Post }o--|| User : "written by"
+"#,
+    );
+
+    let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "migration/src/m20250102_030405_create_users.rs",
+            "migration/src/m20250102_030406_create_posts.rs",
+        ]
+    );
+
+    let expected = r#"use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "posts",
+            &[
+                ("id", ColType::PkAuto),
+                ("title", ColType::String),
+                ("price", ColType::DecimalLenNull(10, 2)),
+            ],
+            &[
+                ("users", "author"),
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "posts").await
+    }
+}
+"#;
+    assert_eq!(files[1].1, expected);
+}
+
+#[test]
+fn test_relationship_references_and_join_tables() {
+    let files = migrations(
+        r#"
+User {
+    int id PK
+}
+Post {
+    uuid id PK
+}
+Tag {
+    int id PK
+}
+User ||--o{ Post : writes
+Post }o--o{ Tag : tagged
+"#,
+    );
+
+    assert_eq!(files.len(), 4);
+    assert!(files[1].1.contains("(\"id\", ColType::PkUuid),"));
+    assert!(files[1].1.contains("            &[\n                (\"user\", \"\"),\n            ],"));
+    assert_eq!(files[3].0, "migration/src/m20250102_030408_create_join_table_post_and_tag.rs");
+    assert!(files[3].1.contains("create_join_table(\n            m,\n            \"post_tags\","));
+}
+
+#[test]
+fn test_one_to_one_relationship_adds_reference() {
+    let files = migrations(
+        r#"
+User {
+    int id PK
+}
+Profile {
+    int id PK
+}
+User ||--|| Profile : has
+"#,
+    );
+
+    assert_eq!(files[1].0, "migration/src/m20250102_030406_create_profiles.rs");
+    assert!(files[1].1.contains("            &[\n                (\"user\", \"\"),\n            ],"));
+}
+
+#[test]
+fn test_cycles_become_add_reference_migrations() {
+    let files = migrations(
+        r#"
+User {
+    int id PK
+    int team_id FK
+}
+Team {
+    int id PK
+    int user_id FK
+}
+"#,
+    );
+
+    assert_eq!(files[2].0, "migration/src/m20250102_030407_add_team_ref_to_users.rs");
+    assert!(files[2].1.contains("add_reference(m, \"users\", \"team\", \"\").await"));
+    assert!(files[2].1.contains("remove_reference(m, \"users\", \"team\", \"\").await"));
+    assert!(files[0].1.contains("            &[],\n"));
+}
+
+#[test]
+fn test_col_types() {
+    let col = |t: &str| col_type(&LocoDataType::parse(t).unwrap());
+    assert_eq!(col("string"), "StringNull");
+    assert_eq!(col("int!"), "Integer");
+    assert_eq!(col("uuid^"), "UuidUniq");
+    assert_eq!(col("tstz"), "TimestampWithTimeZoneNull");
+    assert_eq!(col("binary_len!(16)"), "BinaryLen(16)");
+    assert_eq!(col("array<int>"), "ArrayNull(ArrayColType::Int)");
+}
+
+#[test]
+fn test_register_migrations_in_loco_lib_rs() {
+    let lib_rs = r#"#![allow(elided_lifetimes_in_paths)]
+#![allow(clippy::wildcard_imports)]
+pub use sea_orm_migration::prelude::*;
+
+mod m20220101_000001_users;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20220101_000001_users::Migration),
+            // inject-above (do not remove this comment)
+        ]
+    }
+}
+"#;
+    let modules = vec!["m20250102_030405_create_posts".to_string(), "m20220101_000001_users".to_string()];
+    let updated = register_migrations(Some(lib_rs), &modules).unwrap();
+
+    assert!(updated.contains("mod m20220101_000001_users;\nmod m20250102_030405_create_posts;\n\npub struct Migrator;"));
+    assert!(updated.contains(
+        "            Box::new(m20220101_000001_users::Migration),\n            Box::new(m20250102_030405_create_posts::Migration),\n            // inject-above"
+    ));
+    assert_eq!(updated.matches("m20220101_000001_users::Migration").count(), 1);
+
+    // Migratorの無いファイルは書き換えない
+    assert!(register_migrations(Some("fn main() {}\n"), &modules).is_err());
+    // lib.rs が無ければ新しく作る
+    assert!(register_migrations(None, &modules).unwrap().contains("impl MigratorTrait for Migrator"));
+}
+
+#[test]
+fn test_migration_action() {
+    assert_eq!(migration_action("m20250102_030405_create_users"), "create_users");
+    assert_eq!(migration_action("m20250102_030405_add_team_ref_to_users"), "add_team_ref_to_users");
+    // 同じテーブルに対する別の処理とは区別する
+    assert_ne!(migration_action("m20220101_000001_drop_users"), migration_action("m20250102_030405_create_users"));
+    assert_eq!(migration_action("lib"), "lib");
+}
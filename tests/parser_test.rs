@@ -0,0 +1,333 @@
+use triton::generator::LocoGenerator;
+use triton::parser::MermaidParser;
+
+#[test]
+fn test_type_modifiers() {
+    let mut parser = MermaidParser::new();
+    let content = r#"
+User {
+    int id PK
+    string! email
+    string^ nickname
+    text bio
+}
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    let user = &schema.entities[0];
+
+    // 修飾子付きの属性も取りこぼさない
+    assert_eq!(user.attributes.len(), 4);
+
+    let email = user.attributes.iter().find(|a| a.name == "email").unwrap();
+    assert_eq!(email.data_type, "string!");
+    assert!(!email.is_nullable);
+
+    let nickname = user.attributes.iter().find(|a| a.name == "nickname").unwrap();
+    assert_eq!(nickname.data_type, "string^");
+    assert!(!nickname.is_nullable);
+
+    let bio = user.attributes.iter().find(|a| a.name == "bio").unwrap();
+    assert_eq!(bio.data_type, "text");
+    assert!(bio.is_nullable);
+
+    let generator = LocoGenerator::new();
+    let command = generator.generate_scaffold_command(user);
+
+    assert!(command.contains("email:string!"));
+    assert!(command.contains("nickname:string^"));
+    assert!(command.contains("bio:text"));
+}
+
+#[test]
+fn test_type_modifiers_with_keys() {
+    let mut parser = MermaidParser::new();
+    let content = r#"
+Post {
+    int! id PK
+    int^ user_id FK
+}
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    let post = &schema.entities[0];
+
+    assert_eq!(post.attributes.len(), 2);
+    assert!(post.attributes[0].is_primary_key);
+    assert!(post.attributes[1].is_foreign_key);
+    assert_eq!(post.attributes[1].data_type, "int^");
+}
+
+#[test]
+fn test_attribute_comments() {
+    let mut parser = MermaidParser::new();
+    let content = r#"
+User {
+    int id PK "primary key"
+    string email "Unique"
+    string name "Require"
+    int group_id FK "Require"
+    text bio "self introduction"
+}
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    let user = &schema.entities[0];
+
+    assert_eq!(user.attributes.len(), 5);
+    assert_eq!(user.attributes[0].comment.as_deref(), Some("primary key"));
+
+    let email = &user.attributes[1];
+    assert_eq!(email.comment.as_deref(), Some("Unique"));
+    assert_eq!(email.data_type, "string^");
+    assert!(!email.is_nullable);
+
+    let name = &user.attributes[2];
+    assert_eq!(name.data_type, "string!");
+    assert!(!name.is_nullable);
+
+    // キーワードを含まないコメントは制約に影響しない
+    let bio = &user.attributes[4];
+    assert_eq!(bio.comment.as_deref(), Some("self introduction"));
+    assert_eq!(bio.data_type, "text");
+    assert!(bio.is_nullable);
+
+    let generator = LocoGenerator::new();
+    let command = generator.generate_scaffold_command(user);
+
+    assert!(command.contains("email:string^"));
+    assert!(command.contains("name:string!"));
+    assert!(command.contains("bio:text"));
+}
+
+#[test]
+fn test_comma_separated_keys() {
+    let mut parser = MermaidParser::new();
+    let content = r#"
+Membership {
+    int user_id PK, FK
+    int group_id PK,FK "group"
+    string code UK
+    int legacy_id PK FK
+    string email
+}
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    let membership = &schema.entities[0];
+
+    assert_eq!(membership.attributes.len(), 5);
+
+    let user_id = &membership.attributes[0];
+    assert!(user_id.is_primary_key);
+    assert!(user_id.is_foreign_key);
+    assert!(!user_id.is_unique);
+
+    let group_id = &membership.attributes[1];
+    assert!(group_id.is_primary_key && group_id.is_foreign_key);
+    assert_eq!(group_id.comment.as_deref(), Some("group"));
+
+    // UK はユニーク型として扱う
+    let code = &membership.attributes[2];
+    assert!(code.is_unique);
+    assert!(!code.is_primary_key);
+    assert_eq!(code.data_type, "string^");
+
+    let legacy_id = &membership.attributes[3];
+    assert!(legacy_id.is_primary_key && legacy_id.is_foreign_key);
+
+    assert!(!membership.attributes[4].is_unique);
+
+    let generator = LocoGenerator::new();
+    let command = generator.generate_scaffold_command(membership);
+    assert!(command.contains("code:string^"));
+}
+
+#[test]
+fn test_quoted_entity_names_and_aliases() {
+    let mut parser = MermaidParser::new();
+    let content = r#"erDiagram
+    "Order Item" {
+        int id PK
+    }
+    p[Person] {
+        int id PK
+    }
+    cust["Customer Account"] {
+        int id PK
+    }
+    p ||--o{ "Order Item" : "orders"
+    "Order Item" }o--|| cust : "billed to"
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    assert!(parser.problems().is_empty());
+    assert_eq!(schema.entities.len(), 3);
+
+    let order_item = &schema.entities[0];
+    assert_eq!(order_item.name, "Order Item");
+    assert_eq!(order_item.alias, None);
+    assert_eq!(order_item.model_name(), "OrderItem");
+
+    let person = &schema.entities[1];
+    assert_eq!(person.name, "p");
+    assert_eq!(person.alias.as_deref(), Some("Person"));
+    assert_eq!(person.model_name(), "Person");
+
+    // 表示名が識別子でなければ識別子側を使う
+    let customer = &schema.entities[2];
+    assert_eq!(customer.alias.as_deref(), Some("Customer Account"));
+    assert_eq!(customer.model_name(), "cust");
+
+    assert_eq!(schema.relationships.len(), 2);
+    assert_eq!(schema.relationships[0].from_entity, "p");
+    assert_eq!(schema.relationships[0].to_entity, "Order Item");
+    assert_eq!(schema.relationships[1].to_entity, "cust");
+
+    let generator = LocoGenerator::new();
+    assert!(generator.validate_relationship_entities(&schema).is_empty());
+
+    let commands = generator.generate_commands(&schema);
+    assert!(commands.contains(&"cargo loco generate scaffold OrderItem --api".to_string()));
+    assert!(commands.contains(&"cargo loco generate scaffold Person --api".to_string()));
+    assert!(commands.contains(&"cargo loco generate migration AddPersonRefToOrderItem person:references".to_string()));
+    assert!(commands.contains(&"cargo loco generate migration AddCustRefToOrderItem cust:references".to_string()));
+}
+
+#[test]
+fn test_mermaid_comments_are_skipped() {
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    let content = r#"%%{init: {"theme": "forest"}}%%
+erDiagram
+    %% User ||--o{ Post : "commented out"
+    User {
+        %% string legacy_name
+        int id PK
+    }
+    %% Post {
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    assert_eq!(schema.entities.len(), 1);
+    assert_eq!(schema.entities[0].attributes.len(), 1);
+    assert!(schema.relationships.is_empty());
+}
+
+#[test]
+fn test_triton_annotations() {
+    let mut parser = MermaidParser::new();
+    let content = r#"erDiagram
+    %% triton: scaffold=api
+    %% 通常のコメントは注釈に影響しない
+    %% triton: table=blog_posts, readonly
+    Post {
+        int id PK
+        %% triton: references=User
+        int author_id FK
+        string title
+    }
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    assert!(parser.problems().is_empty());
+
+    let post = &schema.entities[0];
+    assert_eq!(post.annotation("scaffold"), Some("api"));
+    assert_eq!(post.annotation("table"), Some("blog_posts"));
+    assert_eq!(post.annotation("readonly"), Some("true"));
+
+    assert_eq!(post.attributes[1].annotation("references"), Some("User"));
+    // 注釈は直後の1つにだけ付く
+    assert!(post.attributes[0].annotations.is_empty());
+    assert!(post.attributes[2].annotations.is_empty());
+}
+
+#[test]
+fn test_dangling_annotation() {
+    let mut parser = MermaidParser::new();
+    let content = r#"erDiagram
+    User {
+        int id PK
+        %% triton: references=Group
+    }
+    %% triton: scaffold=html
+    User ||--o{ Post : "has many"
+    %% triton: scaffold=api
+"#;
+
+    parser.parse(content).unwrap();
+    let lines: Vec<usize> = parser.problems().iter().map(|p| p.span().line).collect();
+    assert_eq!(lines, vec![4, 6, 8]);
+    assert!(parser
+        .problems()
+        .iter()
+        .all(|p| matches!(p, triton::error::ParseError::DanglingAnnotation { .. })));
+}
+
+#[test]
+fn test_entities_closed_on_the_same_line() {
+    let mut parser = MermaidParser::new().with_strict_mode(true);
+    let content = r#"erDiagram
+    Tag {}
+    Item { int id PK string^ name "label" int owner_id FK }
+    Post { string! title
+        text body
+    }
+    Post ||--o{ Tag : x
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    let names: Vec<&str> = schema.entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["Tag", "Item", "Post"]);
+    assert!(schema.entities[0].attributes.is_empty());
+
+    let item = &schema.entities[1];
+    let attrs: Vec<(&str, &str)> = item.attributes.iter().map(|a| (a.name.as_str(), a.data_type.as_str())).collect();
+    assert_eq!(attrs, vec![("id", "int"), ("name", "string^"), ("owner_id", "int")]);
+    assert!(item.attributes[0].is_primary_key);
+    assert_eq!(item.attributes[1].comment.as_deref(), Some("label"));
+    assert!(item.attributes[2].is_foreign_key);
+
+    let post: Vec<&str> = schema.entities[2].attributes.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(post, vec!["title", "body"]);
+    assert_eq!(schema.relationships.len(), 1);
+
+    // 解釈できない記述と閉じた後ろの記述は報告する
+    let mut parser = MermaidParser::new();
+    parser.parse("Tag { string } extra\n").unwrap();
+    let problems = parser.problems();
+    assert_eq!(problems.len(), 2);
+    assert!(matches!(&problems[0], triton::error::ParseError::InvalidAttribute { entity, .. } if entity == "Tag"));
+    assert!(matches!(&problems[1], triton::error::ParseError::UnrecognizedLine { .. }));
+    assert_eq!(problems[1].span().column, 16);
+}
+
+#[test]
+fn test_sql_type_aliases() {
+    let mut parser = MermaidParser::new();
+    let content = r#"
+Account {
+    int id PK
+    varchar! email
+    integer login_count
+    BIGINT balance
+    boolean active
+    timestamp last_login_at
+    timestamptz! verified_at
+    numeric^ code
+}
+"#;
+
+    let schema = parser.parse(content).unwrap();
+    let types: Vec<&str> = schema.entities[0]
+        .attributes
+        .iter()
+        .map(|a| a.data_type.as_str())
+        .collect();
+
+    assert_eq!(
+        types,
+        vec!["int", "string!", "int", "big_int", "bool", "date_time", "tstz!", "decimal^"]
+    );
+    assert!(parser.problems().is_empty());
+}
@@ -0,0 +1,66 @@
+use triton::parser::MermaidParser;
+use triton::types::*;
+
+fn parse_single(line: &str) -> Relationship {
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(line).unwrap();
+    assert_eq!(schema.relationships.len(), 1, "関係が解釈されていません: {}", line);
+    schema.relationships[0].clone()
+}
+
+#[test]
+fn test_all_cardinality_symbols() {
+    let cases = [
+        ("A |o--o| B : \"x\"", Cardinality::ZeroOrOne, Cardinality::ZeroOrOne),
+        ("A ||--|| B : \"x\"", Cardinality::One, Cardinality::One),
+        ("A }o--o{ B : \"x\"", Cardinality::ZeroOrMany, Cardinality::ZeroOrMany),
+        ("A }|--|{ B : \"x\"", Cardinality::OneOrMany, Cardinality::OneOrMany),
+        ("A ||--o{ B : \"x\"", Cardinality::One, Cardinality::ZeroOrMany),
+        ("A |o--|{ B : \"x\"", Cardinality::ZeroOrOne, Cardinality::OneOrMany),
+        ("A }|--o| B : \"x\"", Cardinality::OneOrMany, Cardinality::ZeroOrOne),
+        ("A }o--|| B : \"x\"", Cardinality::ZeroOrMany, Cardinality::One),
+    ];
+
+    for (line, from, to) in cases {
+        let rel = parse_single(line);
+        assert_eq!(rel.from_entity, "A");
+        assert_eq!(rel.to_entity, "B");
+        assert_eq!(rel.from_cardinality, from, "{}", line);
+        assert_eq!(rel.to_cardinality, to, "{}", line);
+        assert!(rel.is_identifying);
+    }
+}
+
+#[test]
+fn test_relationship_types_from_cardinalities() {
+    assert_eq!(parse_single("A ||--o| B : x").relationship_type, RelationshipType::OneToOne);
+    assert_eq!(parse_single("A ||--|{ B : x").relationship_type, RelationshipType::OneToMany);
+    assert_eq!(parse_single("A }o--|| B : x").relationship_type, RelationshipType::OneToMany);
+    assert_eq!(parse_single("A }|--o{ B : x").relationship_type, RelationshipType::ManyToMany);
+}
+
+#[test]
+fn test_non_identifying_relationship() {
+    let rel = parse_single("Person }o..o{ Car : \"drives\"");
+    assert!(!rel.is_identifying);
+    assert_eq!(rel.relationship_type, RelationshipType::ManyToMany);
+}
+
+#[test]
+fn test_cardinality_aliases() {
+    let rel = parse_single("Car 1 to zero or more Driver : allows");
+    assert_eq!(rel.from_entity, "Car");
+    assert_eq!(rel.from_cardinality, Cardinality::One);
+    assert_eq!(rel.to_cardinality, Cardinality::ZeroOrMany);
+    assert!(rel.is_identifying);
+
+    let rel = parse_single("Person one or more optionally to only one Address : \"lives at\"");
+    assert_eq!(rel.from_cardinality, Cardinality::OneOrMany);
+    assert_eq!(rel.to_cardinality, Cardinality::One);
+    assert!(!rel.is_identifying);
+
+    let rel = parse_single("Entity1 many(0) to 1+ Entity2 : links");
+    assert_eq!(rel.from_entity, "Entity1");
+    assert_eq!(rel.from_cardinality, Cardinality::ZeroOrMany);
+    assert_eq!(rel.to_cardinality, Cardinality::OneOrMany);
+}
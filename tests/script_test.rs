@@ -0,0 +1,85 @@
+use triton::generator::LocoGenerator;
+use triton::parser::MermaidParser;
+use triton::script::render_script;
+
+fn script_for(content: &str) -> String {
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    let commands = LocoGenerator::new().generate_plan(&schema);
+    render_script(&commands, "model.mermaid")
+}
+
+#[test]
+fn test_script_header() {
+    let script = script_for("User {\n    int id PK\n}\n");
+
+    assert!(script.starts_with("#!/usr/bin/env bash\n# Generated by triton from model.mermaid\nset -euo pipefail\n"));
+    assert!(script.contains("grep -q 'loco-rs' Cargo.toml"));
+    assert!(script.ends_with("echo \"==> done\"\n"));
+}
+
+#[test]
+fn test_scaffold_is_guarded_by_model_file() {
+    let content = r#"
+Category {
+    int id PK
+    string^ name
+}
+Post {
+    int id PK
+}
+Post }o--|| Category : "belongs to"
+"#;
+    let script = script_for(content);
+
+    let guard = "\
+echo '==> [1/3] cargo loco generate scaffold Category name:string^ --api'
+if [ -f src/models/categories.rs ]; then
+  echo '    skip: src/models/categories.rs already exists'
+else
+  cargo loco generate scaffold Category name:string^ --api
+fi
+";
+    assert!(script.contains(guard));
+    assert!(script.contains("if [ -f src/models/posts.rs ]; then"));
+
+    // マイグレーションはガードせず順番に実行する
+    assert!(script.contains(
+        "echo '==> [3/3] cargo loco generate migration AddCategoryRefToPost category:references'\n\
+         cargo loco generate migration AddCategoryRefToPost category:references\n"
+    ));
+}
+
+#[test]
+fn test_plan_marks_model_creating_commands() {
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse("OrderItem {\n    int id PK\n}\nOrder ||--o{ OrderItem : has").unwrap();
+    let plan = LocoGenerator::new().generate_plan(&schema);
+
+    assert_eq!(plan.len(), 2);
+    assert_eq!(plan[0].model.as_deref(), Some("OrderItem"));
+    assert_eq!(plan[1].model, None);
+}
+
+#[test]
+fn test_guard_uses_loco_model_file() {
+    let content = r#"
+Person {
+    int id PK
+}
+%% triton: table=blog_posts
+Post {
+    int id PK
+}
+"#;
+    let script = script_for(content);
+
+    // `table=` 注釈に関わらず、Locoはモデル名からファイル名を決める
+    assert!(script.contains("if [ -f src/models/people.rs ]; then"));
+    assert!(script.contains("if [ -f src/models/posts.rs ]; then"));
+
+    let schema = MermaidParser::new().parse(content).unwrap();
+    let diagnostics = LocoGenerator::new().validate_table_annotations(&schema);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Post の table 注釈 'blog_posts' はLocoのコマンドには反映されません");
+}
@@ -0,0 +1,139 @@
+use triton::parser::MermaidParser;
+use triton::sql::{Dialect, SqlGenerator};
+use triton::types::LocoDataType;
+
+fn ddl(content: &str, dialect: Dialect) -> String {
+    let mut parser = MermaidParser::new();
+    let schema = parser.parse(content).unwrap();
+    SqlGenerator::new(dialect).generate(&schema)
+}
+
+const BLOG: &str = r#"
+User {
+    int id PK
+    string^ email
+    text bio
+}
+Post {
+    int id PK
+    string! title
+    decimal_len!(10,2) price
+    int author_id FK
+}
+Tag {
+    uuid id PK
+}
+User ||--o{ Post : writes
+Post }o--o{ Tag : tagged
+"#;
+
+#[test]
+fn test_postgres_create_table() {
+    let sql = ddl(BLOG, Dialect::Postgres);
+    let expected = r#"CREATE TABLE "posts" (
+    "created_at" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "id" SERIAL PRIMARY KEY,
+    "title" VARCHAR NOT NULL,
+    "price" DECIMAL(10, 2) NOT NULL,
+    "author_id" INTEGER NOT NULL,
+    CONSTRAINT "fk-posts-author_id-to-users" FOREIGN KEY ("author_id") REFERENCES "users" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);"#;
+    assert!(sql.contains(expected), "{}", sql);
+    assert!(sql.contains("    \"email\" VARCHAR NOT NULL UNIQUE,\n    \"bio\" TEXT\n);"));
+    assert!(sql.find("CREATE TABLE \"users\"") < sql.find("CREATE TABLE \"posts\""));
+}
+
+#[test]
+fn test_join_table() {
+    let sql = ddl(BLOG, Dialect::Postgres);
+    assert!(sql.contains("    \"post_id\" INTEGER NOT NULL,\n    \"tag_id\" UUID NOT NULL,\n    PRIMARY KEY (\"post_id\", \"tag_id\"),\n"));
+    assert!(sql.contains("FOREIGN KEY (\"tag_id\") REFERENCES \"tags\" (\"id\") ON DELETE CASCADE"));
+}
+
+#[test]
+fn test_dialects() {
+    let mysql = ddl(BLOG, Dialect::Mysql);
+    assert!(mysql.contains("    `id` INT NOT NULL AUTO_INCREMENT PRIMARY KEY,\n    `title` VARCHAR(255) NOT NULL,"));
+    assert!(mysql.contains("    `id` BINARY(16) NOT NULL PRIMARY KEY\n"));
+
+    // MySQLは TEXT・JSON にユニーク制約を付けられないので、出力せずに警告する
+    let content = "User {\n    int id PK\n    string^ email\n    text^ bio\n    jsonb^ meta\n}\n";
+    let mysql = ddl(content, Dialect::Mysql);
+    assert!(mysql.contains("    `email` VARCHAR(255) NOT NULL UNIQUE,\n    `bio` TEXT NOT NULL,\n    `meta` JSON NOT NULL\n"));
+    let schema = MermaidParser::new().parse(content).unwrap();
+    let messages: Vec<String> = SqlGenerator::new(Dialect::Mysql).diagnostics(&schema).into_iter().map(|d| d.message).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "User.bio の型 TEXT はMySQLでユニーク制約を付けられないため、UNIQUE を出力しません",
+            "User.meta の型 JSON はMySQLでユニーク制約を付けられないため、UNIQUE を出力しません",
+        ]
+    );
+    assert!(SqlGenerator::new(Dialect::Postgres).diagnostics(&schema).is_empty());
+
+    let sqlite = ddl(BLOG, Dialect::Sqlite);
+    assert!(sqlite.contains("    \"id\" INTEGER PRIMARY KEY AUTOINCREMENT,\n"));
+    assert!(sqlite.contains("    \"created_at\" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,\n"));
+}
+
+#[test]
+fn test_optional_parent_matches_loco_references() {
+    let sql = ddl(
+        r#"
+User {
+    int id PK
+}
+Post {
+    int id PK
+}
+User |o--o{ Post : writes
+"#,
+        Dialect::Postgres,
+    );
+    assert!(sql.contains("    \"user_id\" INTEGER NOT NULL,\n"));
+    assert!(sql.contains("REFERENCES \"users\" (\"id\") ON DELETE CASCADE ON UPDATE CASCADE"));
+}
+
+#[test]
+fn test_cycles_become_alter_table() {
+    let content = r#"
+User {
+    int id PK
+    int team_id FK
+}
+Team {
+    int id PK
+    int user_id FK
+}
+"#;
+    let postgres = ddl(content, Dialect::Postgres);
+    assert!(postgres.ends_with(
+        "ALTER TABLE \"users\" ADD CONSTRAINT \"fk-users-team_id-to-teams\" FOREIGN KEY (\"team_id\") REFERENCES \"teams\" (\"id\") ON DELETE CASCADE ON UPDATE CASCADE;\n"
+    ));
+    assert!(postgres.contains("    \"team_id\" INTEGER NOT NULL\n);"));
+
+    // SQLiteは作成時に参照先を確認しないので、そのまま制約に含める
+    let sqlite = ddl(content, Dialect::Sqlite);
+    assert!(!sqlite.contains("ALTER TABLE"));
+    assert!(sqlite.contains("CONSTRAINT \"fk-users-team_id-to-teams\""));
+}
+
+#[test]
+fn test_column_types() {
+    let column = |dialect: Dialect, t: &str| SqlGenerator::new(dialect).column_type(&LocoDataType::parse(t).unwrap());
+    assert_eq!(column(Dialect::Postgres, "jsonb"), "JSONB");
+    assert_eq!(column(Dialect::Sqlite, "jsonb"), "TEXT");
+    assert_eq!(column(Dialect::Postgres, "array<string>"), "VARCHAR[]");
+    assert_eq!(column(Dialect::Mysql, "array<string>"), "JSON");
+    assert_eq!(column(Dialect::Mysql, "big_unsigned"), "BIGINT UNSIGNED");
+    assert_eq!(column(Dialect::Mysql, "binary_len(16)"), "BINARY(16)");
+    assert_eq!(column(Dialect::Postgres, "tstz"), "TIMESTAMP WITH TIME ZONE");
+}
+
+#[test]
+fn test_parse_dialect() {
+    assert_eq!("PostgreSQL".parse::<Dialect>(), Ok(Dialect::Postgres));
+    assert_eq!("sqlite".parse::<Dialect>(), Ok(Dialect::Sqlite));
+    assert!("oracle".parse::<Dialect>().is_err());
+}
@@ -0,0 +1,125 @@
+use triton::types::{Constraint, LocoBaseType, LocoDataType, TypeParams};
+use triton::DataTypeError;
+
+#[test]
+fn test_every_type_round_trips() {
+    for base in LocoBaseType::ALL {
+        for constraint in [Constraint::Nullable, Constraint::Required, Constraint::Unique] {
+            let Ok(data_type) = LocoDataType::new(base, constraint) else {
+                continue;
+            };
+            // 引数が必須の型は引数を付けて確認する
+            let data_type = match base {
+                LocoBaseType::DecimalLen => data_type.with_params(&["10", "2"]).unwrap(),
+                LocoBaseType::BinaryLen => data_type.with_params(&["16"]).unwrap(),
+                LocoBaseType::Array => data_type.with_params(&["string"]).unwrap(),
+                _ => data_type,
+            };
+            let text = data_type.to_string();
+            assert_eq!(text.parse::<LocoDataType>(), Ok(data_type), "{}", text);
+            assert_eq!(LocoDataType::from_mermaid_type(&text), data_type, "{}", text);
+        }
+    }
+}
+
+#[test]
+fn test_constraint_follows_suffix() {
+    // 以前は修飾子と型の対応が入れ替わっていた型
+    assert_eq!(LocoDataType::from_mermaid_type("big_unsigned").to_string(), "big_unsigned");
+    assert_eq!(LocoDataType::from_mermaid_type("big_unsigned!").to_string(), "big_unsigned!");
+    assert_eq!(LocoDataType::from_mermaid_type("big_unsigned^").to_string(), "big_unsigned^");
+    assert_eq!(LocoDataType::from_mermaid_type("array:int").to_string(), "array:int");
+    assert_eq!(LocoDataType::from_mermaid_type("array!:int").to_string(), "array!:int");
+    assert_eq!(LocoDataType::from_mermaid_type("array^:int").to_string(), "array^:int");
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        "interval".parse::<LocoDataType>(),
+        Err(DataTypeError::UnknownType("interval".to_string()))
+    );
+    assert_eq!(
+        "bool^".parse::<LocoDataType>(),
+        Err(DataTypeError::UnsupportedConstraint("bool^".to_string()))
+    );
+    // 別名はFromStrでは受け付けず、parseで解決する
+    assert!("boolean".parse::<LocoDataType>().is_err());
+    assert_eq!(
+        LocoDataType::parse("BOOLEAN!"),
+        Ok(LocoDataType::new(LocoBaseType::Bool, Constraint::Required).unwrap())
+    );
+    // uniqueを指定できない型はnull許容にせず、エラーにする（lenientな変換では必須として扱う）
+    assert_eq!(
+        LocoDataType::parse("boolean^"),
+        Err(DataTypeError::UnsupportedConstraint("bool^".to_string()))
+    );
+    assert_eq!(LocoDataType::from_mermaid_type("bool^").to_string(), "bool!");
+}
+
+#[test]
+fn test_parameterized_types() {
+    let decimal = LocoDataType::parse("decimal_len(10,2)!").unwrap();
+    assert_eq!(decimal.params, Some(TypeParams::Precision { precision: 10, scale: 2 }));
+    assert_eq!(decimal.to_string(), "decimal_len!:10:2");
+
+    // 修飾子は括弧の前でもよく、Locoの形式もそのまま読める
+    assert_eq!(LocoDataType::parse("decimal_len!(10, 2)"), Ok(decimal));
+    assert_eq!("decimal_len!:10:2".parse::<LocoDataType>(), Ok(decimal));
+
+    assert_eq!(LocoDataType::parse("binary_len(16)").unwrap().to_string(), "binary_len:16");
+    assert_eq!(LocoDataType::parse("array<varchar>^").unwrap().to_string(), "array^:string");
+}
+
+#[test]
+fn test_type_parameters_are_validated() {
+    let reason = |mermaid_type: &str| match LocoDataType::parse(mermaid_type) {
+        Err(DataTypeError::InvalidParameters { reason, .. }) => reason,
+        other => panic!("{}: {:?}", mermaid_type, other),
+    };
+
+    assert_eq!(reason("decimal_len(0,0)"), "桁数は1〜65で指定してください（0）");
+    assert_eq!(reason("decimal_len(10,12)"), "小数点以下の桁数は0〜10で指定してください（12）");
+    assert_eq!(reason("decimal_len(10)"), "`decimal_len(桁数,小数点以下の桁数)` の形式で指定してください");
+    assert_eq!(reason("binary_len(x)"), "'x' は数値ではありません");
+    assert_eq!(reason("binary_len(1024)"), "バイト数は1〜255で指定してください（1024）");
+    assert_eq!(reason("array<json>"), "要素の型は string, int, float, bool のいずれかを指定してください（json）");
+    assert_eq!(reason("int(11)"), "この型は引数を取りません");
+    // 引数が必須の型は、引数が無ければエラー
+    assert_eq!(reason("decimal_len!"), "`decimal_len(桁数,小数点以下の桁数)` の形式で指定してください");
+    assert_eq!(reason("array"), "`array<要素の型>` の形式で指定してください");
+    assert!("binary_len".parse::<LocoDataType>().is_err());
+
+    // lenientな変換では引数の要らない型にする
+    assert_eq!(LocoDataType::from_mermaid_type("decimal_len(70,2)!").to_string(), "decimal!");
+    assert_eq!(LocoDataType::from_mermaid_type("binary_len").to_string(), "blob");
+    assert_eq!(LocoDataType::from_mermaid_type("array^").to_string(), "array^:string");
+}
+
+#[test]
+fn test_table_names_follow_loco_inflection() {
+    use triton::naming::to_table_name;
+
+    let cases = [
+        ("User", "users"),
+        ("Category", "categories"),
+        ("Day", "days"),
+        ("Address", "addresses"),
+        ("Person", "people"),
+        ("SalesPerson", "sales_people"),
+        ("Child", "children"),
+        ("Woman", "women"),
+        ("Knife", "knives"),
+        ("Shelf", "shelves"),
+        ("Analysis", "analyses"),
+        ("Medium", "media"),
+        ("Index", "indices"),
+        ("Ox", "oxen"),
+        ("Box", "boxes"),
+        ("Equipment", "equipment"),
+        ("NewsItem", "news_items"),
+    ];
+    for (model, table) in cases {
+        assert_eq!(to_table_name(model), table, "{}", model);
+    }
+}